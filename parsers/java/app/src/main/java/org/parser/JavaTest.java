@@ -7,13 +7,15 @@ public class JavaTest {
     String classPath;
     @JsonProperty("method_name")
     String methodName;
+    TestLines lines;
 
     public JavaTest() {
     }
 
-    public JavaTest(String classPath, String methodName) {
+    public JavaTest(String classPath, String methodName, TestLines lines) {
         this.classPath = classPath;
         this.methodName = methodName;
+        this.lines = lines;
     }
 
     public String getClassPath() {
@@ -31,4 +33,12 @@ public class JavaTest {
     public void setMethodName(String methodName) {
         this.methodName = methodName;
     }
+
+    public TestLines getLines() {
+        return lines;
+    }
+
+    public void setLines(TestLines lines) {
+        this.lines = lines;
+    }
 }
@@ -97,7 +97,8 @@ public class JavaTests {
                     }
                     var relativePath = getRelativePath(file);
                     long modifiedTime = attrs.lastModifiedTime().toInstant().toEpochMilli();
-                    if (modifiedTime > timestamp) {
+                    // Tests cached by older versions have no lines
+                    if (modifiedTime > timestamp || missesLines(relativePath.toString())) {
                         var newTests = getTestMethodsWithClassPaths(file);
                         if (newTests.isEmpty()) {
                             // Remove entry if file exists but now has no tests
@@ -173,13 +174,22 @@ public class JavaTests {
                     .toList();
 
                 for (CtMethod<?> method : testMethods) {
-                    result.add(new JavaTest(classPath, method.getSimpleName()));
+                    TestLines lines = null;
+                    if (method.getPosition().isValidPosition()) {
+                        lines = new TestLines(method.getPosition().getLine(), method.getPosition().getEndLine());
+                    }
+                    result.add(new JavaTest(classPath, method.getSimpleName(), lines));
                 }
             }
         }
 
         return result;
     }
+    private boolean missesLines(String relativePath) {
+        var cachedTests = tests.get(relativePath);
+        return cachedTests != null && cachedTests.stream().anyMatch(test -> test.getLines() == null);
+    }
+
     private boolean isHidden(Path path) throws IOException {
         return Files.isHidden(path);
     }
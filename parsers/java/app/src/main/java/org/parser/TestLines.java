@@ -0,0 +1,30 @@
+package org.parser;
+
+public class TestLines {
+    int start;
+    int end;
+
+    public TestLines() {
+    }
+
+    public TestLines(int start, int end) {
+        this.start = start;
+        this.end = end;
+    }
+
+    public int getStart() {
+        return start;
+    }
+
+    public void setStart(int start) {
+        this.start = start;
+    }
+
+    public int getEnd() {
+        return end;
+    }
+
+    public void setEnd(int end) {
+        this.end = end;
+    }
+}
@@ -31,6 +31,8 @@ class ParserTest {
         assertEquals(testOne.size(), 1);
         assertEquals(testOne.getFirst().getClassPath(), "tests.java.a.TestOne");
         assertEquals(testOne.getFirst().getMethodName(), "one");
+        assertEquals(testOne.getFirst().getLines().getStart(), 7);
+        assertEquals(testOne.getFirst().getLines().getEnd(), 9);
 
         var testTwo = javaTests.getTests().get("java/a/testTwo.java");
         assertEquals(testTwo.size(), 2);
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::errors::FztError;
+
+use super::types::HistoryEntry;
+
+/// Version of files written before the envelope existed
+pub const LEGACY_VERSION: u32 = 0;
+/// Bump if the layout of any `Tests` implementation changes, outdated caches get rebuilt
+pub const INVENTORY_VERSION: u32 = 1;
+/// Bump if the history layout changes and add a migration to `migrate_history`
+pub const HISTORY_VERSION: u32 = 2;
+pub const META_VERSION: u32 = 1;
+pub const SUITES_VERSION: u32 = 1;
+pub const RESULTS_VERSION: u32 = 1;
+/// Version of files written by `fzt export`
+pub const PORTABLE_VERSION: u32 = 1;
+
+/// Wrapper around every file written by the cache manager
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub version: u32,
+    pub data: T,
+}
+
+fn is_envelope(value: &Value) -> bool {
+    value.as_object().is_some_and(|object| {
+        object.len() == 2
+            && object.get("version").is_some_and(Value::is_u64)
+            && object.contains_key("data")
+    })
+}
+
+/// Split the content of a cache file into its version and data
+pub fn open(content: &str) -> Result<(u32, Value), FztError> {
+    let value: Value = serde_json::from_str(content)?;
+    if is_envelope(&value) {
+        let envelope: Envelope<Value> = serde_json::from_value(value)?;
+        Ok((envelope.version, envelope.data))
+    } else {
+        Ok((LEGACY_VERSION, value))
+    }
+}
+
+pub fn seal<T: Serialize>(version: u32, data: &T) -> Result<String, FztError> {
+    Ok(serde_json::to_string(&Envelope { version, data })?)
+}
+
+/// Seal data that is already serialized
+pub fn seal_json(version: u32, data: &str) -> String {
+    format!("{{\"version\":{},\"data\":{}}}", version, data)
+}
+
+fn newer_version_error(file: &str, version: u32) -> FztError {
+    FztError::UserError(format!(
+        "The {file} was written by a newer fzt version (schema {version}). Update fzt or run `fzt --clear-history`."
+    ))
+}
+
+/// Version 1 stored every selection, most recent first. Duplicates are merged into one entry.
+fn migrate_history_v1(data: Value) -> Result<Value, FztError> {
+    let history: Vec<Vec<String>> = serde_json::from_value(data)?;
+    let mut entries: Vec<HistoryEntry> = vec![];
+    for selection in history
+        .into_iter()
+        .filter(|selection| !selection.is_empty())
+    {
+        match entries
+            .iter_mut()
+            .find(|entry| entry.same_selection(selection.as_slice()))
+        {
+            Some(entry) => entry.count += 1,
+            None => entries.push(HistoryEntry::new(selection, 0)),
+        }
+    }
+    Ok(serde_json::to_value(entries)?)
+}
+
+/// Migrate history data of any older version to `HISTORY_VERSION`
+pub fn migrate_history<T: DeserializeOwned>(version: u32, data: Value) -> Result<T, FztError> {
+    let data = match version {
+        // Version 1 only added the envelope
+        LEGACY_VERSION | 1 => migrate_history_v1(data)?,
+        HISTORY_VERSION => data,
+        version => return Err(newer_version_error("history", version)),
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Migrate meta data of any older version to `META_VERSION`
+pub fn migrate_meta<T: DeserializeOwned>(version: u32, data: Value) -> Result<T, FztError> {
+    let data = match version {
+        LEGACY_VERSION | META_VERSION => data,
+        version => return Err(newer_version_error("project meta data", version)),
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Migrate saved suites of any older version to `SUITES_VERSION`
+pub fn migrate_suites<T: DeserializeOwned>(version: u32, data: Value) -> Result<T, FztError> {
+    let data = match version {
+        SUITES_VERSION => data,
+        LEGACY_VERSION => {
+            return Err(FztError::GeneralParsingError(
+                "Saved suites are not versioned".to_string(),
+            ));
+        }
+        version => return Err(newer_version_error("saved suites", version)),
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Migrate test results of any older version to `RESULTS_VERSION`
+pub fn migrate_results<T: DeserializeOwned>(version: u32, data: Value) -> Result<T, FztError> {
+    let data = match version {
+        RESULTS_VERSION => data,
+        LEGACY_VERSION => {
+            return Err(FztError::GeneralParsingError(
+                "Test results are not versioned".to_string(),
+            ));
+        }
+        version => return Err(newer_version_error("test results", version)),
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+pub fn migrate_portable<T: DeserializeOwned>(version: u32, data: Value) -> Result<T, FztError> {
+    let data = match version {
+        PORTABLE_VERSION => data,
+        LEGACY_VERSION => {
+            return Err(FztError::InvalidArgument(
+                "The file was not written by `fzt export`".to_string(),
+            ));
+        }
+        version => {
+            return Err(FztError::UserError(format!(
+                "The file was exported by a newer fzt version (schema {version}). Update fzt."
+            )));
+        }
+    };
+    Ok(serde_json::from_value(data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn open_sealed_and_legacy() {
+        let sealed = seal_json(INVENTORY_VERSION, "{\"root_folder\":\"/tmp\"}");
+        let (version, data) = open(sealed.as_str()).unwrap();
+        assert_eq!(version, INVENTORY_VERSION);
+        assert_eq!(data["root_folder"], "/tmp");
+
+        let (version, data) = open("[[\"a\"],[\"b\"],[\"a\"]]").unwrap();
+        assert_eq!(version, LEGACY_VERSION);
+        let history: Vec<HistoryEntry> = migrate_history(version, data).unwrap();
+        assert_eq!(
+            history,
+            vec![
+                HistoryEntry {
+                    selection: vec!["a".to_string()],
+                    count: 2,
+                    last_used: 0,
+                    outcome: None
+                },
+                HistoryEntry::new(vec!["b".to_string()], 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_newer_history() {
+        let (version, data) = open(seal_json(HISTORY_VERSION + 1, "[]").as_str()).unwrap();
+        assert!(migrate_history::<Vec<HistoryEntry>>(version, data).is_err());
+    }
+}
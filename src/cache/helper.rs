@@ -1,14 +1,73 @@
-use std::env;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 use sha2::{Digest, Sha256};
 
 use crate::errors::FztError;
 
-pub fn project_hash() -> Result<String, FztError> {
+const PROJECT_ROOT_MARKERS: [&str; 4] = [".git", "Cargo.toml", "pyproject.toml", "settings.gradle"];
+
+/// Nearest ancestor of `start` (including itself) that contains one of the project root markers
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| {
+            PROJECT_ROOT_MARKERS
+                .iter()
+                .any(|marker| dir.join(marker).exists())
+        })
+        .map(|dir| dir.to_path_buf())
+}
+
+/// Root of the project the current directory belongs to, falls back to the current directory
+pub fn project_root() -> Result<PathBuf, FztError> {
     let path = env::current_dir()?;
-    let root_dir = path.to_string_lossy().to_string();
+    Ok(find_project_root(path.as_path()).unwrap_or(path))
+}
+
+/// Directory all cache, history and meta data files are stored in
+pub fn cache_location() -> PathBuf {
+    let mut cache_location = home::home_dir().expect("Could not find home directory");
+    cache_location.push(".fzt");
+    cache_location
+}
+
+pub fn root_hash(root: &Path) -> String {
+    let root_dir = root.to_string_lossy().to_string();
     let mut hasher = Sha256::new();
     hasher.update(root_dir.as_bytes());
     let result = hasher.finalize();
-    Ok(format!("{:x}", result))
+    format!("{:x}", result)
+}
+
+pub fn project_hash() -> Result<String, FztError> {
+    Ok(root_hash(project_root()?.as_path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn find_nearest_project_root() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("crate/src/nested")).unwrap();
+        fs::create_dir(root.join(".git")).unwrap();
+        assert_eq!(
+            find_project_root(root.join("crate/src/nested").as_path()),
+            Some(root.to_path_buf())
+        );
+        fs::write(root.join("crate/Cargo.toml"), "").unwrap();
+        assert_eq!(
+            find_project_root(root.join("crate/src/nested").as_path()),
+            Some(root.join("crate"))
+        );
+        assert_eq!(find_project_root(root), Some(root.to_path_buf()));
+    }
 }
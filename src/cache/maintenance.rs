@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::errors::FztError;
+
+use super::envelope::open;
+
+const HASH_LENGTH: usize = 64;
+
+/// All files in the cache location that belong to one project
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectCache {
+    pub hash: String,
+    pub root_folder: Option<String>,
+    pub runner: Option<String>,
+    // Inventory name (e.g. `rust-cargo`) and its path
+    pub inventories: Vec<(String, PathBuf)>,
+    pub files: Vec<PathBuf>,
+    pub size: u64,
+}
+
+impl ProjectCache {
+    fn new(hash: &str) -> Self {
+        Self {
+            hash: hash.to_string(),
+            root_folder: None,
+            runner: None,
+            inventories: vec![],
+            files: vec![],
+            size: 0,
+        }
+    }
+
+    /// A project is stale if its root folder is known and does not exist anymore
+    pub fn is_stale(&self) -> bool {
+        self.root_folder
+            .as_ref()
+            .is_some_and(|root_folder| !Path::new(root_folder).exists())
+    }
+}
+
+fn project_file_hash(file_name: &str) -> Option<&str> {
+    let hash = file_name.get(..HASH_LENGTH)?;
+    let is_hash =
+        hash.chars().all(|ch| ch.is_ascii_hexdigit()) && file_name[HASH_LENGTH..].starts_with('-');
+    is_hash.then_some(hash)
+}
+
+fn read_data(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
+    open(content.as_str()).ok().map(|(_, data)| data)
+}
+
+/// Group all files in the cache location by project
+pub fn scan(cache_location: &Path) -> Result<Vec<ProjectCache>, FztError> {
+    let mut projects: BTreeMap<String, ProjectCache> = BTreeMap::new();
+    if !cache_location.exists() {
+        return Ok(vec![]);
+    }
+    for entry in fs::read_dir(cache_location)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(hash) = project_file_hash(file_name.as_str()) else {
+            continue;
+        };
+        let project = projects
+            .entry(hash.to_string())
+            .or_insert(ProjectCache::new(hash));
+        let path = entry.path();
+        project.size += entry.metadata()?.len();
+        project.files.push(path.clone());
+
+        let name = &file_name[HASH_LENGTH + 1..];
+        if name == "metadata.json" {
+            project.runner = read_data(&path)
+                .and_then(|data| data["runner_name"].as_str().map(|name| name.to_string()));
+        } else if let Some(inventory) = name.strip_suffix(".json")
+            && !inventory.contains("-history-")
+            && !inventory.ends_with("-suites")
+            && !inventory.ends_with("-results")
+        {
+            if let Some(root_folder) =
+                read_data(&path).and_then(|data| data["root_folder"].as_str().map(String::from))
+            {
+                project.root_folder = Some(root_folder);
+            }
+            project.inventories.push((inventory.to_string(), path));
+        }
+    }
+    Ok(projects
+        .into_values()
+        .map(|mut project| {
+            project.files.sort();
+            project.inventories.sort();
+            project
+        })
+        .collect())
+}
+
+/// Remove all files of projects whose root folder does not exist anymore
+pub fn prune(cache_location: &Path) -> Result<Vec<ProjectCache>, FztError> {
+    let stale: Vec<ProjectCache> = scan(cache_location)?
+        .into_iter()
+        .filter(|project| project.is_stale())
+        .collect();
+    for project in stale.iter() {
+        for file in project.files.iter() {
+            fs::remove_file(file)?;
+        }
+    }
+    Ok(stale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const HASH_A: &str = "27a36a1154760d8b2da67998542ff9eb87dc8c53c1089f0ff1abe407f7ff50a2";
+    const HASH_B: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn scan_and_prune_projects() {
+        let cache = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        let cache_path = cache.path();
+        fs::write(
+            cache_path.join(format!("{HASH_A}-rust-cargo.json")),
+            format!(
+                "{{\"version\":1,\"data\":{{\"root_folder\":\"{}\"}}}}",
+                project.path().display()
+            ),
+        )
+        .unwrap();
+        fs::write(
+            cache_path.join(format!("{HASH_A}-metadata.json")),
+            "{\"version\":1,\"data\":{\"runner_name\":\"RustCargoRunner\"}}",
+        )
+        .unwrap();
+        fs::write(
+            cache_path.join(format!("{HASH_B}-pytest.json")),
+            "{\"version\":1,\"data\":{\"root_folder\":\"/does/not/exist\"}}",
+        )
+        .unwrap();
+        fs::write(
+            cache_path.join(format!("{HASH_B}-pytest-history-test-granularity.json")),
+            "[]",
+        )
+        .unwrap();
+        fs::write(cache_path.join("config"), "mode=test").unwrap();
+        // Lock of a first history write, the history file does not exist yet
+        let lock = cache_path.join(format!(
+            "{HASH_A}-rust-cargo-history-test-granularity.json.lock"
+        ));
+        fs::write(&lock, "").unwrap();
+
+        let projects = scan(cache_path).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].hash, HASH_B);
+        assert!(projects[0].is_stale());
+        assert_eq!(projects[0].files.len(), 2);
+        assert_eq!(projects[0].inventories.len(), 1);
+        assert_eq!(projects[1].runner, Some("RustCargoRunner".to_string()));
+        assert!(!projects[1].is_stale());
+
+        let pruned = prune(cache_path).unwrap();
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(scan(cache_path).unwrap().len(), 1);
+        assert!(cache_path.join("config").exists());
+        assert!(lock.exists());
+    }
+}
@@ -1,18 +1,32 @@
-use home::home_dir;
+use colored::Colorize;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{
-    collections::VecDeque,
-    fs::File,
-    io::{BufReader, BufWriter, Write},
+    collections::{BTreeMap, VecDeque},
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::errors::FztError;
+use crate::{
+    errors::FztError,
+    runner::config::FilterMode,
+    runtime::{FailedTest, RunStatus, TestResult},
+    utils::file::{backup_corrupted, lock_exclusive, remove_locked, write_atomic},
+};
 
-use super::Cache;
+use super::{
+    Cache,
+    envelope::{
+        HISTORY_VERSION, INVENTORY_VERSION, META_VERSION, RESULTS_VERSION, SUITES_VERSION,
+        migrate_history, migrate_meta, migrate_results, migrate_suites, open, seal, seal_json,
+    },
+    helper::cache_location,
+    types::{HistoryEntry, Suite, TestRecord},
+};
 
 const HISTORY_SIZE: usize = 200;
 
-#[derive(Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HistoryGranularity {
     Test,
     File,
@@ -21,6 +35,18 @@ pub enum HistoryGranularity {
     Append,
 }
 
+impl From<&FilterMode> for HistoryGranularity {
+    fn from(value: &FilterMode) -> Self {
+        match value {
+            FilterMode::Test => HistoryGranularity::Test,
+            FilterMode::File => HistoryGranularity::File,
+            FilterMode::Directory => HistoryGranularity::Directory,
+            FilterMode::RunTime => HistoryGranularity::RunTime,
+            FilterMode::Append => HistoryGranularity::Append,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LocalCacheManager {
     cache_file: PathBuf,
@@ -29,22 +55,27 @@ pub struct LocalCacheManager {
     history_directory_granularity: PathBuf,
     history_runtime_granularity: PathBuf,
     history_continues_append_granularity: PathBuf,
+    suites_file: PathBuf,
+    results_file: PathBuf,
 }
 
 impl LocalCacheManager {
     pub fn new_failed_tests(project_id: &str) -> Self {
-        let mut cache_location = home_dir().expect("Could not find home directory");
-        cache_location.push(".fzt");
-
+        let cache_location = cache_location();
         let mut cache_manager = Self::new(format!("{}-failed", project_id).as_str());
-        // The test cache file still stays the same
+        // The test cache file, saved suites and test results still stay the same
         cache_manager.cache_file = cache_location.join(format!("{}.json", project_id));
+        cache_manager.suites_file = cache_location.join(format!("{}-suites.json", project_id));
+        cache_manager.results_file = cache_location.join(format!("{}-results.json", project_id));
         cache_manager
     }
 
     pub fn new(project_id: &str) -> Self {
-        let mut cache_location = home_dir().expect("Could not find home directory");
-        cache_location.push(".fzt");
+        Self::new_in(&cache_location(), project_id)
+    }
+
+    /// Cache files of the project in `cache_location`
+    pub fn new_in(cache_location: &Path, project_id: &str) -> Self {
         let cache_file = cache_location.join(format!("{}.json", project_id));
         let history_test_granularity =
             cache_location.join(format!("{}-history-test-granularity.json", project_id));
@@ -58,6 +89,8 @@ impl LocalCacheManager {
             "{}-history-continues-append-granularity.json",
             project_id
         ));
+        let suites_file = cache_location.join(format!("{}-suites.json", project_id));
+        let results_file = cache_location.join(format!("{}-results.json", project_id));
         Self {
             cache_file,
             history_test_granularity,
@@ -65,48 +98,103 @@ impl LocalCacheManager {
             history_directory_granularity,
             history_runtime_granularity,
             history_continues_append_granularity,
+            suites_file,
+            results_file,
         }
     }
 
-    pub fn new_from_path(
-        cache_file: PathBuf,
-        history_test_granularity: PathBuf,
-        history_file_granularity: PathBuf,
-        history_directory_granularity: PathBuf,
-        history_runtime_granularity: PathBuf,
-        history_continues_append_granularity: PathBuf,
-    ) -> Self {
-        Self {
-            cache_file,
-            history_test_granularity,
-            history_file_granularity,
-            history_directory_granularity,
-            history_runtime_granularity,
-            history_continues_append_granularity,
+    pub fn save_meta(project_id: &str, meta_data: &str) -> Result<(), FztError> {
+        let path = cache_location().join(format!("{}-metadata.json", project_id));
+        write_atomic(&path, seal_json(META_VERSION, meta_data).as_str())
+    }
+
+    pub fn get_meta<T: DeserializeOwned>(project_id: &str) -> Result<Option<T>, FztError> {
+        let path = cache_location().join(format!("{}-metadata.json", project_id));
+
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        match open(content.as_str()).and_then(|(version, data)| migrate_meta(version, data)) {
+            Ok(meta_data) => Ok(Some(meta_data)),
+            Err(FztError::UserError(error)) => Err(FztError::UserError(error)),
+            Err(_) => {
+                backup_corrupted(&path)?;
+                Ok(None)
+            }
         }
     }
 
-    pub fn save_meta(project_id: &str, meta_data: &str) -> Result<(), FztError> {
-        let mut meta_location = home_dir().expect("Could not find home directory");
-        meta_location.push(".fzt");
-        let path = meta_location.join(format!("{}-metadata.json", project_id));
-        let file = File::create(&path)?;
-        let mut writer = BufWriter::new(file);
-        writer.write(meta_data.as_bytes())?;
-        Ok(())
+    /// Has to be called while holding the history lock
+    /// Entries are ordered by recency, most recent first
+    fn read_history(&self, history_file: &Path) -> Result<VecDeque<HistoryEntry>, FztError> {
+        if !history_file.exists() {
+            return Ok(VecDeque::new());
+        }
+        let content = std::fs::read_to_string(history_file)?;
+        let history = open(content.as_str()).and_then(|(version, data)| {
+            Ok((
+                version,
+                migrate_history::<VecDeque<HistoryEntry>>(version, data)?,
+            ))
+        });
+        match history {
+            Ok((version, history)) => {
+                if version != HISTORY_VERSION {
+                    self.write_history(history_file, &history)?;
+                }
+                Ok(history)
+            }
+            // Written by a newer version, do not overwrite it
+            Err(FztError::UserError(error)) => Err(FztError::UserError(error)),
+            Err(_) => {
+                backup_corrupted(history_file)?;
+                Ok(VecDeque::new())
+            }
+        }
+    }
+
+    fn write_history(
+        &self,
+        history_file: &Path,
+        history: &VecDeque<HistoryEntry>,
+    ) -> Result<(), FztError> {
+        write_atomic(history_file, seal(HISTORY_VERSION, history)?.as_str())
     }
 
-    pub fn get_meta(project_id: &str) -> Result<Option<BufReader<File>>, FztError> {
-        let mut meta_location = home_dir().expect("Could not find home directory");
-        meta_location.push(".fzt");
-        let path = meta_location.join(format!("{}-metadata.json", project_id));
+    /// Has to be called while holding the suites lock
+    fn read_suites(&self) -> Result<BTreeMap<String, Suite>, FztError> {
+        if !self.suites_file.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = std::fs::read_to_string(&self.suites_file)?;
+        match open(content.as_str()).and_then(|(version, data)| migrate_suites(version, data)) {
+            Ok(suites) => Ok(suites),
+            Err(FztError::UserError(error)) => Err(FztError::UserError(error)),
+            Err(_) => {
+                backup_corrupted(&self.suites_file)?;
+                Ok(BTreeMap::new())
+            }
+        }
+    }
 
-        if !Path::new(&path).exists() {
-            Ok(None)
-        } else {
-            let entry = File::open(&path)?;
-            let reader = BufReader::new(entry);
-            Ok(Some(reader))
+    fn write_suites(&self, suites: &BTreeMap<String, Suite>) -> Result<(), FztError> {
+        write_atomic(&self.suites_file, seal(SUITES_VERSION, suites)?.as_str())
+    }
+
+    /// Has to be called while holding the results lock
+    fn read_results(&self) -> Result<BTreeMap<String, TestRecord>, FztError> {
+        if !self.results_file.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = std::fs::read_to_string(&self.results_file)?;
+        match open(content.as_str()).and_then(|(version, data)| migrate_results(version, data)) {
+            Ok(results) => Ok(results),
+            Err(FztError::UserError(error)) => Err(FztError::UserError(error)),
+            Err(_) => {
+                backup_corrupted(&self.results_file)?;
+                Ok(BTreeMap::new())
+            }
         }
     }
 
@@ -122,20 +210,27 @@ impl LocalCacheManager {
 }
 
 impl Cache for LocalCacheManager {
-    fn get_entry(&self) -> Result<Option<BufReader<File>>, FztError> {
+    fn get_entry(&self) -> Result<Option<String>, FztError> {
         if !Path::new(&self.cache_file).exists() {
-            Ok(None)
-        } else {
-            let entry = File::open(&self.cache_file)?;
-            Ok(Some(BufReader::new(entry)))
+            return Ok(None);
+        }
+        match open(std::fs::read_to_string(&self.cache_file)?.as_str()) {
+            Ok((INVENTORY_VERSION, data)) => Ok(Some(data.to_string())),
+            _ => {
+                eprintln!(
+                    "{}",
+                    "Test cache is outdated or corrupted, rebuilding it.".yellow()
+                );
+                Ok(None)
+            }
         }
     }
 
     fn add_entry(&self, entry: &str) -> Result<(), FztError> {
-        let file = File::create(&self.cache_file)?;
-        let mut writer = BufWriter::new(file);
-        writer.write(entry.as_bytes())?;
-        Ok(())
+        write_atomic(
+            &self.cache_file,
+            seal_json(INVENTORY_VERSION, entry).as_str(),
+        )
     }
 
     fn clear_cache(&self) -> Result<(), FztError> {
@@ -146,21 +241,11 @@ impl Cache for LocalCacheManager {
     }
 
     fn clear_history(&self) -> Result<(), FztError> {
-        if Path::new(&self.history_test_granularity).exists() {
-            std::fs::remove_file(&self.history_test_granularity)?;
-        }
-        if Path::new(&self.history_file_granularity).exists() {
-            std::fs::remove_file(&self.history_file_granularity)?;
-        }
-        if Path::new(&self.history_directory_granularity).exists() {
-            std::fs::remove_file(&self.history_directory_granularity)?;
-        }
-        if Path::new(&self.history_runtime_granularity).exists() {
-            std::fs::remove_file(&self.history_runtime_granularity)?;
-        }
-        if Path::new(&self.history_continues_append_granularity).exists() {
-            std::fs::remove_file(&self.history_continues_append_granularity)?;
-        }
+        remove_locked(&self.history_test_granularity)?;
+        remove_locked(&self.history_file_granularity)?;
+        remove_locked(&self.history_directory_granularity)?;
+        remove_locked(&self.history_runtime_granularity)?;
+        remove_locked(&self.history_continues_append_granularity)?;
         Ok(())
     }
 
@@ -175,21 +260,41 @@ impl Cache for LocalCacheManager {
 
         let history_file = self.get_history_file(granularity);
 
-        let mut history = if !Path::new(history_file).exists() {
-            VecDeque::new()
-        } else {
-            let file = File::open(history_file)?;
-            let reader = BufReader::new(file);
-            let content: VecDeque<Vec<String>> = serde_json::from_reader(reader)?;
-            content
+        let _lock = lock_exclusive(history_file)?;
+        let mut history = self.read_history(history_file)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let entry = match history
+            .iter()
+            .position(|entry| entry.same_selection(selection))
+            .and_then(|index| history.remove(index))
+        {
+            Some(mut entry) => {
+                entry.count += 1;
+                entry.last_used = now;
+                entry.selection = selection.to_vec();
+                entry
+            }
+            None => HistoryEntry::new(selection.to_vec(), now),
         };
-        history.push_front(selection.to_vec());
-        let file = File::create(history_file)?;
-        let mut writer = BufWriter::new(file);
+        history.push_front(entry);
         if history.len() > HISTORY_SIZE {
             history.pop_back();
         }
-        serde_json::to_writer(&mut writer, &history)?;
+        self.write_history(history_file, &history)
+    }
+
+    fn record_outcome(
+        &self,
+        granularity: &HistoryGranularity,
+        outcome: RunStatus,
+    ) -> Result<(), FztError> {
+        let history_file = self.get_history_file(granularity);
+        let _lock = lock_exclusive(history_file)?;
+        let mut history = self.read_history(history_file)?;
+        if let Some(entry) = history.front_mut() {
+            entry.outcome = Some(outcome);
+            self.write_history(history_file, &history)?;
+        }
         Ok(())
     }
 
@@ -198,15 +303,12 @@ impl Cache for LocalCacheManager {
         granularity: &HistoryGranularity,
     ) -> Result<Vec<String>, FztError> {
         let history_file = self.get_history_file(granularity);
-
-        if !Path::new(history_file).exists() {
-            Ok(vec![])
-        } else {
-            let file = File::open(history_file)?;
-            let reader = BufReader::new(file);
-            let content: Vec<Vec<String>> = serde_json::from_reader(reader)?;
-            Ok(content.first().map(|tests| tests.clone()).unwrap_or(vec![]))
-        }
+        let _lock = lock_exclusive(history_file)?;
+        let history = self.read_history(history_file)?;
+        Ok(history
+            .front()
+            .map(|entry| entry.selection.clone())
+            .unwrap_or(vec![]))
     }
 
     fn history(&self, granularity: &HistoryGranularity) -> Result<Vec<Vec<String>>, FztError> {
@@ -215,53 +317,182 @@ impl Cache for LocalCacheManager {
         if !Path::new(history_file).exists() {
             Ok(vec![vec![]])
         } else {
-            let file = File::open(history_file)?;
-            let reader = BufReader::new(file);
-            let content: Vec<Vec<String>> = serde_json::from_reader(reader)?;
-            Ok(content)
+            let _lock = lock_exclusive(history_file)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let mut history: Vec<HistoryEntry> = self.read_history(history_file)?.into();
+            // Stable sort keeps the recency order for equal frecency
+            history.sort_by(|a, b| b.frecency(now).total_cmp(&a.frecency(now)));
+            Ok(history.into_iter().map(|entry| entry.selection).collect())
+        }
+    }
+
+    fn import_history(
+        &self,
+        selections: &[Vec<String>],
+        granularity: &HistoryGranularity,
+    ) -> Result<usize, FztError> {
+        let history_file = self.get_history_file(granularity);
+        let _lock = lock_exclusive(history_file)?;
+        let mut history = self.read_history(history_file)?;
+        let mut imported = 0;
+        for selection in selections.iter().filter(|selection| !selection.is_empty()) {
+            if history.len() >= HISTORY_SIZE {
+                break;
+            }
+            if history.iter().any(|entry| entry.same_selection(selection)) {
+                continue;
+            }
+            // Unknown usage, imported entries rank below the own ones
+            history.push_back(HistoryEntry::new(selection.clone(), 0));
+            imported += 1;
+        }
+        if imported > 0 {
+            self.write_history(history_file, &history)?;
+        }
+        Ok(imported)
+    }
+
+    fn suites(&self) -> Result<BTreeMap<String, Suite>, FztError> {
+        let _lock = lock_exclusive(&self.suites_file)?;
+        self.read_suites()
+    }
+
+    fn save_suite(&self, name: &str, suite: &Suite) -> Result<(), FztError> {
+        let _lock = lock_exclusive(&self.suites_file)?;
+        let mut suites = self.read_suites()?;
+        suites.insert(name.to_string(), suite.clone());
+        self.write_suites(&suites)
+    }
+
+    fn delete_suite(&self, name: &str) -> Result<bool, FztError> {
+        let _lock = lock_exclusive(&self.suites_file)?;
+        let mut suites = self.read_suites()?;
+        if suites.remove(name).is_none() {
+            return Ok(false);
         }
+        self.write_suites(&suites)?;
+        Ok(true)
+    }
+
+    fn test_records(&self) -> Result<BTreeMap<String, TestRecord>, FztError> {
+        let _lock = lock_exclusive(&self.results_file)?;
+        self.read_results()
+    }
+
+    fn record_test_results(
+        &self,
+        results: &[TestResult],
+        failed_tests: &[FailedTest],
+    ) -> Result<(), FztError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let _lock = lock_exclusive(&self.results_file)?;
+        let mut records = self.read_results()?;
+        for result in results.iter() {
+            records
+                .entry(result.name.clone())
+                .and_modify(|record| record.update(result))
+                .or_insert_with(|| TestRecord::new(result));
+        }
+        for failed_test in failed_tests.iter() {
+            if let Some(record) = records.get_mut(&failed_test.name) {
+                record.set_failure(&failed_test.error_msg);
+            }
+        }
+        write_atomic(
+            &self.results_file,
+            seal(RESULTS_VERSION, &records)?.as_str(),
+        )
+    }
+
+    fn record_flaky_tests(&self, names: &[String]) -> Result<(), FztError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        let _lock = lock_exclusive(&self.results_file)?;
+        let mut records = self.read_results()?;
+        for name in names.iter() {
+            if let Some(record) = records.get_mut(name) {
+                record.flaky += 1;
+            }
+        }
+        write_atomic(
+            &self.results_file,
+            seal(RESULTS_VERSION, &records)?.as_str(),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::Read;
+    use std::{
+        fs::File,
+        io::{Read, Write},
+    };
 
     use super::*;
+    use crate::{runtime::TestStatus, tests::test_provider::SelectGranularity};
     use pretty_assertions::assert_eq;
     use tempfile::NamedTempFile;
 
     #[test]
     fn get_non_existing_entry() {
         let path = PathBuf::from("/ifhoeowhfoew/oihsoehwofihwoih.json");
-        let manager = LocalCacheManager::new_from_path(
-            path,
-            PathBuf::from("file.path()"),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
-        );
+        let manager = LocalCacheManager {
+            cache_file: path,
+            ..missing_files()
+        };
         let result = manager.get_entry().unwrap();
         assert!(result.is_none());
     }
 
     #[test]
     fn get_existing_entry() {
-        let file = NamedTempFile::new().unwrap();
+        let mut file = NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "{{\"version\":{INVENTORY_VERSION},\"data\":{{\"tests\":[]}}}}"
+        )
+        .unwrap();
         let path = PathBuf::from(file.path());
-        let manager = LocalCacheManager::new_from_path(
-            path,
-            PathBuf::from("file.path()"),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
+        let manager = LocalCacheManager {
+            cache_file: path,
+            ..missing_files()
+        };
+        let entry = manager.get_entry().unwrap().unwrap();
+        assert_eq!(entry, String::from("{\"tests\":[]}"));
+    }
+
+    #[test]
+    fn get_legacy_entry() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{{\"root_folder\":\"/tmp\",\"tests\":{{}}}}").unwrap();
+        let manager = LocalCacheManager {
+            cache_file: PathBuf::from(file.path()),
+            ..missing_files()
+        };
+        assert!(manager.get_entry().unwrap().is_none());
+    }
+
+    #[test]
+    fn migrate_legacy_history() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "[[\"test_a\"],[\"test_b\",\"test_c\"]]").unwrap();
+        let path = PathBuf::from(file.path());
+        let manager = LocalCacheManager {
+            history_test_granularity: path.clone(),
+            ..missing_files()
+        };
+        assert_eq!(
+            manager.history(&HistoryGranularity::Test).unwrap(),
+            vec![
+                vec!["test_a".to_string()],
+                vec!["test_b".to_string(), "test_c".to_string()]
+            ]
         );
-        let mut reader = manager.get_entry().unwrap().unwrap();
-        let mut file_content = String::new();
-        reader.read_to_string(&mut file_content).unwrap();
-        assert_eq!(file_content, String::from(""));
+        let (version, _) = open(std::fs::read_to_string(path).unwrap().as_str()).unwrap();
+        assert_eq!(version, HISTORY_VERSION);
     }
 
     #[test]
@@ -269,20 +500,244 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, "Old").unwrap();
         let path = PathBuf::from(file.path());
-        let manager = LocalCacheManager::new_from_path(
-            path.clone(),
-            PathBuf::from("file.path()"),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
-            PathBuf::from(""),
-        );
-        manager.add_entry("New").unwrap();
+        let manager = LocalCacheManager {
+            cache_file: path.clone(),
+            ..missing_files()
+        };
+        manager.add_entry("{}").unwrap();
 
-        let entry = File::open(path).unwrap();
-        let mut reader = BufReader::new(entry);
         let mut file_content = String::new();
-        reader.read_to_string(&mut file_content).unwrap();
-        assert_eq!(file_content, String::from("New"));
+        File::open(path.clone())
+            .unwrap()
+            .read_to_string(&mut file_content)
+            .unwrap();
+        assert_eq!(
+            file_content,
+            format!("{{\"version\":{INVENTORY_VERSION},\"data\":{{}}}}")
+        );
+        assert_eq!(manager.get_entry().unwrap().unwrap(), String::from("{}"));
+    }
+
+    // Files the test does not set are in a directory that does not exist
+    fn missing_files() -> LocalCacheManager {
+        LocalCacheManager::new_in(Path::new("/ifhoeowhfoew"), "oihsoehwofihwoih")
+    }
+
+    fn history_manager(history_file: PathBuf) -> LocalCacheManager {
+        LocalCacheManager {
+            history_test_granularity: history_file,
+            ..missing_files()
+        }
+    }
+
+    #[test]
+    fn concurrent_history_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = history_manager(dir.path().join("history.json"));
+        std::thread::scope(|s| {
+            for thread in 0..8 {
+                let manager = manager.clone();
+                s.spawn(move || {
+                    for run in 0..10 {
+                        manager
+                            .update_history(
+                                &[format!("test_{thread}_{run}")],
+                                &HistoryGranularity::Test,
+                            )
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(
+            manager.history(&HistoryGranularity::Test).unwrap().len(),
+            80
+        );
+    }
+
+    #[test]
+    fn recover_truncated_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let history_file = dir.path().join("history.json");
+        std::fs::write(&history_file, "{\"version\":1,\"data\":[[\"te").unwrap();
+        let manager = history_manager(history_file.clone());
+        assert!(
+            manager
+                .recent_history_command(&HistoryGranularity::Test)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(dir.path().join("history.json.corrupt").exists());
+        manager
+            .update_history(&["test_a".to_string()], &HistoryGranularity::Test)
+            .unwrap();
+        assert_eq!(
+            manager
+                .recent_history_command(&HistoryGranularity::Test)
+                .unwrap(),
+            vec!["test_a".to_string()]
+        );
+    }
+
+    #[test]
+    fn frecency_ranked_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = history_manager(dir.path().join("history.json"));
+        let test_a = vec!["test_a".to_string()];
+        let test_b = vec!["test_b".to_string(), "test_c".to_string()];
+        for selection in [&test_a, &test_b, &test_a, &test_a] {
+            manager
+                .update_history(selection.as_slice(), &HistoryGranularity::Test)
+                .unwrap();
+        }
+        // Same selection in different order is the same entry
+        manager
+            .update_history(
+                &["test_c".to_string(), "test_b".to_string()],
+                &HistoryGranularity::Test,
+            )
+            .unwrap();
+        manager
+            .record_outcome(&HistoryGranularity::Test, RunStatus::TestsFailed)
+            .unwrap();
+
+        assert_eq!(
+            manager.history(&HistoryGranularity::Test).unwrap(),
+            vec![
+                test_a.clone(),
+                vec!["test_c".to_string(), "test_b".to_string()]
+            ]
+        );
+        assert_eq!(
+            manager
+                .recent_history_command(&HistoryGranularity::Test)
+                .unwrap(),
+            vec!["test_c".to_string(), "test_b".to_string()]
+        );
+        let history = manager
+            .read_history(dir.path().join("history.json").as_path())
+            .unwrap();
+        assert_eq!(history[0].count, 2);
+        assert_eq!(history[0].outcome, Some(RunStatus::TestsFailed));
+        assert_eq!(history[1].count, 3);
+    }
+
+    #[test]
+    fn save_and_delete_suites() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LocalCacheManager {
+            suites_file: dir.path().join("suites.json"),
+            ..missing_files()
+        };
+        let smoke = Suite::new(BTreeMap::from([
+            (SelectGranularity::Test, vec!["test_a".to_string()]),
+            (SelectGranularity::File, vec!["tests/test_b.py".to_string()]),
+        ]));
+        let slow = Suite::new(BTreeMap::from([(
+            SelectGranularity::Directory,
+            vec!["tests/slow".to_string()],
+        )]));
+        manager.save_suite("smoke", &smoke).unwrap();
+        manager.save_suite("slow", &slow).unwrap();
+        manager.save_suite("slow", &smoke).unwrap();
+        assert_eq!(
+            manager.suites().unwrap(),
+            BTreeMap::from([
+                ("slow".to_string(), smoke.clone()),
+                ("smoke".to_string(), smoke)
+            ])
+        );
+
+        assert!(manager.delete_suite("slow").unwrap());
+        assert!(!manager.delete_suite("slow").unwrap());
+        assert_eq!(
+            manager.suites().unwrap().keys().collect::<Vec<&String>>(),
+            vec!["smoke"]
+        );
+    }
+
+    #[test]
+    fn import_history_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = history_manager(dir.path().join("history.json"));
+        manager
+            .update_history(&["test_a".to_string()], &HistoryGranularity::Test)
+            .unwrap();
+        let imported = manager
+            .import_history(
+                &[
+                    vec!["test_a".to_string()],
+                    vec![],
+                    vec!["test_c".to_string(), "test_b".to_string()],
+                ],
+                &HistoryGranularity::Test,
+            )
+            .unwrap();
+        assert_eq!(imported, 1);
+        assert_eq!(
+            manager.history(&HistoryGranularity::Test).unwrap(),
+            vec![
+                vec!["test_a".to_string()],
+                vec!["test_c".to_string(), "test_b".to_string()]
+            ]
+        );
+        assert_eq!(
+            manager
+                .recent_history_command(&HistoryGranularity::Test)
+                .unwrap(),
+            vec!["test_a".to_string()]
+        );
+    }
+
+    #[test]
+    fn record_test_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LocalCacheManager {
+            results_file: dir.path().join("results.json"),
+            ..missing_files()
+        };
+        manager
+            .record_test_results(
+                &[
+                    TestResult::new("test_a", TestStatus::Failed, Some(0.5)),
+                    TestResult::new("test_b", TestStatus::Skipped, None),
+                ],
+                &[FailedTest::new("test_a", "assert 1 == 2")],
+            )
+            .unwrap();
+        manager
+            .record_test_results(
+                &[TestResult::new("test_a", TestStatus::Passed, Some(0.2))],
+                &[],
+            )
+            .unwrap();
+        manager
+            .record_flaky_tests(&["test_a".to_string(), "test_c".to_string()])
+            .unwrap();
+        assert_eq!(
+            manager.test_records().unwrap(),
+            BTreeMap::from([
+                (
+                    "test_a".to_string(),
+                    TestRecord {
+                        status: TestStatus::Passed,
+                        duration: Some(0.2),
+                        failures: 1,
+                        failure: None,
+                        flaky: 1,
+                    }
+                ),
+                (
+                    "test_b".to_string(),
+                    TestRecord {
+                        status: TestStatus::Skipped,
+                        duration: None,
+                        failures: 0,
+                        failure: None,
+                        flaky: 0,
+                    }
+                ),
+            ])
+        );
     }
 }
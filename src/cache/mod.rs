@@ -1,15 +1,22 @@
-use std::{fs::File, io::BufReader};
+use std::collections::BTreeMap;
 
 use manager::HistoryGranularity;
+use types::{Suite, TestRecord};
 
-use crate::errors::FztError;
+use crate::{
+    errors::FztError,
+    runtime::{FailedTest, RunStatus, TestResult},
+};
 
+pub mod envelope;
 pub mod helper;
+pub mod maintenance;
 pub mod manager;
 pub mod types;
 
 pub trait Cache {
-    fn get_entry(&self) -> Result<Option<BufReader<File>>, FztError>;
+    /// Cached test inventory, `None` if missing or written with an outdated schema
+    fn get_entry(&self) -> Result<Option<String>, FztError>;
     fn add_entry(&self, entry: &str) -> Result<(), FztError>;
     fn clear_cache(&self) -> Result<(), FztError>;
     fn clear_history(&self) -> Result<(), FztError>;
@@ -18,9 +25,40 @@ pub trait Cache {
         selection: &[String],
         granularity: &HistoryGranularity,
     ) -> Result<(), FztError>;
+    /// Store the outcome of the run of the most recent history entry
+    fn record_outcome(
+        &self,
+        granularity: &HistoryGranularity,
+        outcome: RunStatus,
+    ) -> Result<(), FztError>;
     fn recent_history_command(
         &self,
         granularity: &HistoryGranularity,
     ) -> Result<Vec<String>, FztError>;
+    /// Unique selections ranked by frecency
     fn history(&self, granularity: &HistoryGranularity) -> Result<Vec<Vec<String>>, FztError>;
+    /// Add selections that are not in the history yet behind the existing entries.
+    /// Returns the number of added entries.
+    fn import_history(
+        &self,
+        selections: &[Vec<String>],
+        granularity: &HistoryGranularity,
+    ) -> Result<usize, FztError>;
+    /// Saved suites by name
+    fn suites(&self) -> Result<BTreeMap<String, Suite>, FztError>;
+    /// Save the suite under the name, replacing an existing suite with the same name
+    fn save_suite(&self, name: &str, suite: &Suite) -> Result<(), FztError>;
+    /// Returns `false` if there is no suite with this name
+    fn delete_suite(&self, name: &str) -> Result<bool, FztError>;
+    /// Count a flaky run for each of the tests, names are test item names
+    fn record_flaky_tests(&self, names: &[String]) -> Result<(), FztError>;
+    /// Last result of every test that ran, by test item name
+    fn test_records(&self) -> Result<BTreeMap<String, TestRecord>, FztError>;
+    /// Update the records with the results and failure output of a run,
+    /// names are test item names
+    fn record_test_results(
+        &self,
+        results: &[TestResult],
+        failed_tests: &[FailedTest],
+    ) -> Result<(), FztError>;
 }
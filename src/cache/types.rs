@@ -1,7 +1,12 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    runtime::{RunStatus, TestResult, TestStatus},
+    tests::test_provider::SelectGranularity,
+};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheEntry {
     pub root_folder: String,
@@ -22,3 +27,117 @@ impl CacheEntry {
         }
     }
 }
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// A unique selection in the history, ranked by frecency
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub selection: Vec<String>,
+    pub count: u32,
+    // Seconds since UNIX epoch, 0 if unknown (migrated entries)
+    pub last_used: u64,
+    pub outcome: Option<RunStatus>,
+}
+
+impl HistoryEntry {
+    pub fn new(selection: Vec<String>, last_used: u64) -> Self {
+        Self {
+            selection,
+            count: 1,
+            last_used,
+            outcome: None,
+        }
+    }
+
+    pub fn same_selection(&self, selection: &[String]) -> bool {
+        let mut own = self.selection.clone();
+        let mut other = selection.to_vec();
+        own.sort();
+        other.sort();
+        own == other
+    }
+
+    /// Frequency weighted by how recently the entry was used
+    pub fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_used);
+        let weight = match age {
+            age if age < HOUR => 4.0,
+            age if age < DAY => 2.0,
+            age if age < WEEK => 1.0,
+            _ => 0.5,
+        };
+        self.count as f64 * weight
+    }
+}
+
+/// Selection saved under a name, items are kept per granularity to support mixed append selections
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Suite {
+    pub selection: BTreeMap<SelectGranularity, Vec<String>>,
+}
+
+impl Suite {
+    pub fn new(selection: BTreeMap<SelectGranularity, Vec<String>>) -> Self {
+        Self { selection }
+    }
+
+    pub fn len(&self) -> usize {
+        self.selection.values().map(|items| items.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Last result of a test and how often it failed overall
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TestRecord {
+    pub status: TestStatus,
+    // Seconds, `None` if the runtime does not report it
+    pub duration: Option<f64>,
+    pub failures: u32,
+    // Error message and traceback of the last failure, cleared once the test passes
+    #[serde(default)]
+    pub failure: Option<String>,
+    // Runs in which the test failed and passed on a retry
+    #[serde(default)]
+    pub flaky: u32,
+}
+
+impl TestRecord {
+    pub fn new(result: &TestResult) -> Self {
+        let mut record = Self {
+            status: result.status,
+            duration: None,
+            failures: 0,
+            failure: None,
+            flaky: 0,
+        };
+        record.update(result);
+        record
+    }
+
+    pub fn update(&mut self, result: &TestResult) {
+        self.status = result.status;
+        self.duration = result.duration;
+        match result.status {
+            TestStatus::Failed => self.failures += 1,
+            // The output of an earlier failure is stale
+            TestStatus::Passed => self.failure = None,
+            TestStatus::Skipped => {}
+        }
+    }
+
+    /// Keep the output of the last failure, a failure without output clears it
+    pub fn set_failure(&mut self, error_msg: &str) {
+        self.failure = if error_msg.trim().is_empty() {
+            None
+        } else {
+            Some(error_msg.to_string())
+        };
+    }
+}
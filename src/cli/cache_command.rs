@@ -0,0 +1,142 @@
+use std::{fs, path::Path};
+
+use colored::Colorize;
+use tabled::{Table, Tabled, settings::Style};
+
+use crate::{
+    cache::{
+        envelope::open,
+        helper::{cache_location, find_project_root, project_hash, root_hash},
+        maintenance::{ProjectCache, prune, scan},
+    },
+    errors::FztError,
+};
+
+#[derive(Tabled)]
+struct ProjectRow {
+    #[tabled(rename = "Project")]
+    hash: String,
+    #[tabled(rename = "Root")]
+    root_folder: String,
+    #[tabled(rename = "Runner")]
+    runner: String,
+    #[tabled(rename = "Files")]
+    files: usize,
+    #[tabled(rename = "Size")]
+    size: String,
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        size if size >= 1024 * 1024 => format!("{:.1} MB", size as f64 / (1024.0 * 1024.0)),
+        size if size >= 1024 => format!("{:.1} KB", size as f64 / 1024.0),
+        size => format!("{} B", size),
+    }
+}
+
+impl From<&ProjectCache> for ProjectRow {
+    fn from(project: &ProjectCache) -> Self {
+        let status = if project.root_folder.is_none() {
+            "unknown root"
+        } else if project.is_stale() {
+            "stale"
+        } else {
+            "ok"
+        };
+        let runner = project.runner.clone().unwrap_or(
+            project
+                .inventories
+                .iter()
+                .map(|(inventory, _)| inventory.as_str())
+                .collect::<Vec<&str>>()
+                .join(", "),
+        );
+        Self {
+            hash: project.hash[..12].to_string(),
+            root_folder: project.root_folder.clone().unwrap_or("-".to_string()),
+            runner,
+            files: project.files.len(),
+            size: format_size(project.size),
+            status: status.to_string(),
+        }
+    }
+}
+
+pub fn list_projects() -> Result<(), FztError> {
+    let projects = scan(cache_location().as_path())?;
+    if projects.is_empty() {
+        println!("No cached projects found in {}", cache_location().display());
+        return Ok(());
+    }
+    let mut table = Table::new(projects.iter().map(ProjectRow::from));
+    table.with(Style::rounded());
+    println!("{}", table);
+    Ok(())
+}
+
+fn find_project(project: Option<&str>) -> Result<ProjectCache, FztError> {
+    let hash = match project {
+        None => project_hash()?,
+        Some(path) if Path::new(path).exists() => {
+            let path = fs::canonicalize(path)?;
+            root_hash(find_project_root(path.as_path()).unwrap_or(path).as_path())
+        }
+        Some(hash) => hash.to_string(),
+    };
+    let mut matches: Vec<ProjectCache> = scan(cache_location().as_path())?
+        .into_iter()
+        .filter(|project| project.hash.starts_with(hash.as_str()))
+        .collect();
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(FztError::UserError(format!(
+            "No cached project found for `{}`.",
+            project.unwrap_or("current directory")
+        ))),
+        _ => Err(FztError::UserError(format!(
+            "`{hash}` matches multiple projects, use a longer hash."
+        ))),
+    }
+}
+
+pub fn show_project(project: Option<&str>) -> Result<(), FztError> {
+    let project = find_project(project)?;
+    println!(
+        "{} {}",
+        "Project".bold(),
+        project
+            .root_folder
+            .as_deref()
+            .unwrap_or(project.hash.as_str())
+    );
+    if project.inventories.is_empty() {
+        println!("No test inventory cached.");
+    }
+    for (inventory, path) in project.inventories.iter() {
+        println!("\n{} ({})", inventory.bold(), path.display());
+        let (_, data) = open(fs::read_to_string(path)?.as_str())?;
+        println!("{}", serde_json::to_string_pretty(&data)?);
+    }
+    Ok(())
+}
+
+pub fn prune_projects() -> Result<(), FztError> {
+    let pruned = prune(cache_location().as_path())?;
+    if pruned.is_empty() {
+        println!("Nothing to prune.");
+    }
+    for project in pruned.iter() {
+        println!(
+            "Removed {} files ({}) of {}",
+            project.files.len(),
+            format_size(project.size),
+            project
+                .root_folder
+                .as_deref()
+                .unwrap_or(project.hash.as_str())
+        );
+    }
+    Ok(())
+}
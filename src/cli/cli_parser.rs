@@ -1,19 +1,36 @@
-use std::process::exit;
+use std::{env, path::PathBuf, process::exit, str::FromStr};
 
 use clap::{Command, CommandFactory, FromArgMatches, Parser, Subcommand};
 
 use crate::{
-    cache::helper::project_hash,
+    cache::helper::{project_hash, project_root},
     errors::FztError,
-    runner::config::{FilterMode, Language, Preview, RunnerConfig, RunnerMode},
-    runtime::{Debugger, PythonDebugger},
-    search_engine::{SearchEngine, fzf::FzfSearchEngine},
+    runner::{
+        config::{FilterMode, Language, Preview, RunnerConfig, RunnerMode},
+        flaky::FlakyOptions,
+        list::{ListFormat, ListOptions},
+        portable::PortableCommand,
+        repeat::RepeatOptions,
+        suite::{SuiteCommand, validate_suite_name},
+    },
+    runtime::{Debugger, PythonDebugger, dry_run::DryRun},
+    search_engine::{
+        ConfiguredSearchEngine, SearchEngine,
+        builtin::BuiltinSearchEngine,
+        fzf::{FzfSearchEngine, read_preview_index},
+        preview::{NO_FAILURE_OUTPUT, preview_item},
+        skim::SkimSearchEngine,
+    },
+    tests::test_provider::SelectGranularity,
+    utils::path_resolver::relative_to_root,
 };
 
 use super::{
     Config,
+    cache_command::{list_projects, prune_projects, show_project},
     default::get_default,
     dependency_check::check_dependencies,
+    project_config::{PROJECT_CONFIG_FILE, load_project_config},
     settings::{load_config, update_settings},
 };
 
@@ -31,14 +48,27 @@ fn parse_filter_mode(filter_mode: &str) -> Result<FilterMode, FztError> {
     }
 }
 
+fn parse_list_format(format: &str) -> Result<ListFormat, FztError> {
+    match format.to_lowercase().as_str() {
+        "plain" => Ok(ListFormat::Plain),
+        "json" => Ok(ListFormat::Json),
+        "ndjson" => Ok(ListFormat::Ndjson),
+        _ => Err(FztError::InvalidArgument(format!(
+            "Invalid list format `{}` option. Use 'plain', 'json' or 'ndjson'.",
+            format.to_lowercase().as_str()
+        ))),
+    }
+}
+
 fn parse_preview(preview: &str) -> Result<Preview, FztError> {
     match preview.to_lowercase().as_str() {
         "file" | "f" => Ok(Preview::File),
         "test" | "t" => Ok(Preview::Test),
         "directory" | "d" => Ok(Preview::Directory),
+        "failure" => Ok(Preview::Failure),
         _ => {
             return Err(FztError::InvalidArgument(format!(
-                "Invalid preview `{}` option. Use 'file', 'test', 'directory', 'select', or 's'.",
+                "Invalid preview `{}` option. Use 'file', 'test', 'directory', 'failure', 'select', or 's'.",
                 preview.to_lowercase().as_str()
             )));
         }
@@ -49,7 +79,12 @@ fn parse_preview(preview: &str) -> Result<Preview, FztError> {
 #[command(version, about, long_about = None)]
 #[clap(disable_help_flag = true)]
 struct Cli {
-    #[arg(long, value_parser=["FzF"])]
+    #[arg(
+        long,
+        help = "[Default: FzF]. Fuzzy finder used for all selections. \
+                'builtin' and 'skim' run in the terminal and do not need fzf.",
+        value_parser=["FzF", "builtin", "skim"]
+    )]
     search_engine: Option<String>,
 
     #[arg(
@@ -123,9 +158,11 @@ struct Cli {
         help = "Preview test function symbol or file. \
                 If 'mode' is set to directory, then 'directory' is always used as preview. \
                 Preview is not used if '--history' is set, or granularity is 'runtime'.\
+                'failure' shows the output of the last failure of the test. \
                 Open selection menu if `s` or `select` is provided. \
-                'auto' will select the best mode for the selected 'mode'.",
-        value_parser=["file", "f", "test", "t", "directory", "d", "select", "s", "auto", "a", "none"])]
+                'auto' will select the best mode for the selected 'mode', \
+                and 'failure' for tests if '--failed' is set.",
+        value_parser=["file", "f", "test", "t", "directory", "d", "failure", "select", "s", "auto", "a", "none"])]
     preview: Option<String>,
 
     #[arg(
@@ -136,6 +173,80 @@ struct Cli {
     )]
     all: bool,
 
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "plain",
+        help = "Print the commands passed to the runtime without executing them. \
+                'script' prints them as shell script, per item commands split over \
+                multiple threads run in parallel.",
+        value_parser=["plain", "script"]
+    )]
+    dry_run: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Kill a test item and record it as failed if it runs longer than the timeout. \
+                Items are killed with their whole process group. \
+                nextest and pytest (with pytest-timeout installed) kill single tests themselves. \
+                Otherwise runtimes running all items in one process (pytest, gradle) \
+                kill the run once it takes longer than the timeouts of all items together."
+    )]
+    timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Kill the run and record the tests without result as failed \
+                if all items together run longer than the timeout.",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    batch_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Save the selection as suite under the name, an existing suite is replaced. \
+                Run it again with `fzt suite run <NAME>`."
+    )]
+    save: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 0,
+        value_name = "N",
+        help = "Re-run failed tests up to N times. Tests that pass on a retry \
+                are reported and recorded as flaky instead of failed."
+    )]
+    retries: usize,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        conflicts_with = "retries",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        help = "Run the selected tests N times and show how often each test passed and failed. \
+                The output of failed repetitions is saved to a file."
+    )]
+    repeat: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        conflicts_with = "retries",
+        help = "Repeat the selected tests until they fail, at most '--repeat' times if set"
+    )]
+    until_fail: bool,
+
     #[arg(
         long,
         short,
@@ -169,6 +280,14 @@ struct Cli {
     )]
     failed: bool,
 
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "failed",
+        help = "Exclude tests that passed on a retry before from the failed tests"
+    )]
+    skip_flaky: bool,
+
     #[arg(
         long,
         short,
@@ -211,6 +330,108 @@ enum Commands {
         #[arg(default_value_t = String::from("cargo"), value_parser=["cargo", "nextest"])]
         runtime: String,
     },
+    #[command(about = "Print all test items of the default runner without opening the finder")]
+    List {
+        #[arg(
+            long,
+            short,
+            default_value_t = String::from("test"),
+            help = "Granularity of the listed items",
+            value_parser=["test", "file", "directory", "runtime"]
+        )]
+        granularity: String,
+
+        #[arg(
+            long,
+            default_value_t = String::from("plain"),
+            help = "Output format. 'plain' prints tab separated columns: item, path, runtime arguments, state",
+            value_parser=["plain", "json", "ndjson"]
+        )]
+        format: String,
+    },
+    #[command(
+        about = "Run all items matching one of the patterns without opening the finder. \
+        Items are matched in the granularity set by '--mode'. \
+        Patterns are matched as substring, as glob if they contain '*', '?' or '[', \
+        or as regex if prefixed with 're:'"
+    )]
+    Run {
+        #[arg(required = true)]
+        patterns: Vec<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Record the matched items in the history"
+        )]
+        record: bool,
+    },
+    #[command(about = "Inspect and clean up the cache of all projects in ~/.fzt")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    #[command(about = "List, run, edit and delete suites saved with '--save'")]
+    Suite {
+        #[command(subcommand)]
+        command: SuiteCommands,
+    },
+    #[command(
+        about = "Export the history of all modes and the saved suites as JSON \
+        with items relative to the project root, e.g. to share them in the repository"
+    )]
+    Export {
+        #[arg(help = "File to write to. Prints to stdout if not set")]
+        file: Option<String>,
+    },
+    #[command(about = "Import history and suites exported with 'export'. \
+        Items that do not exist in the project are skipped")]
+    Import { file: String },
+    #[command(about = "List tests that passed on a retry, the most flaky first")]
+    Flaky {
+        #[arg(long, short = 'n', help = "Number of tests shown")]
+        limit: Option<usize>,
+    },
+    // Called by fzf to render the preview of the item under the cursor
+    #[command(name = "__preview", hide = true)]
+    Preview {
+        #[arg(value_parser=["file", "test", "directory", "failure"])]
+        preview: String,
+        item: String,
+        // Failure output or lines of the tests by finder line, tests without lines are searched by name
+        #[arg(long, requires = "index")]
+        index_file: Option<PathBuf>,
+        // Line of the item in the finder
+        #[arg(long)]
+        index: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SuiteCommands {
+    #[command(about = "List all saved suites of the project with their items")]
+    List,
+    #[command(about = "Run all items of the suite without opening the finder")]
+    Run { name: String },
+    #[command(about = "Edit the items of the suite in $VISUAL or $EDITOR")]
+    Edit { name: String },
+    #[command(about = "Delete the suite")]
+    Delete { name: String },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    #[command(about = "List all cached projects with their root folder, runner and cache size")]
+    List,
+    #[command(about = "Pretty print the cached test inventory of a project")]
+    Show {
+        #[arg(help = "Project root folder or hash (prefix). Defaults to the current project")]
+        project: Option<String>,
+    },
+    #[command(
+        about = "Remove all cache files of projects whose root folder does not exist anymore"
+    )]
+    Prune,
 }
 
 fn parse_args(cmd: Command) -> (Cli, Vec<String>) {
@@ -275,11 +496,86 @@ pub fn parse_cli() -> Result<Config, FztError> {
         exit(0);
     }
 
+    if let Some(Commands::Cache { command }) = &cli.command {
+        match command {
+            CacheCommands::List => list_projects()?,
+            CacheCommands::Show { project } => show_project(project.as_deref())?,
+            CacheCommands::Prune => prune_projects()?,
+        }
+        exit(0);
+    }
+
+    if let Some(Commands::Preview {
+        preview,
+        item,
+        index_file,
+        index,
+    }) = &cli.command
+    {
+        // Lines of the finder can carry the last result of the test after a tab
+        let item = item.split('\t').next().unwrap_or_default();
+        let indexed = index_file
+            .as_ref()
+            .zip(*index)
+            .and_then(|(path, index)| read_preview_index(path, index));
+        let preview = parse_preview(preview.as_str())?;
+        let output = match preview {
+            Preview::Failure => indexed.unwrap_or_else(|| NO_FAILURE_OUTPUT.to_string()),
+            _ => preview_item(
+                item,
+                &preview,
+                None,
+                indexed.and_then(|lines| lines.parse().ok()),
+                true,
+            ),
+        };
+        println!("{}", output);
+        exit(0);
+    }
+
+    // Discovery and runtimes run from the project root, so items are always relative to it
+    let working_dir = env::current_dir()?;
+    let root = project_root()?;
+    env::set_current_dir(&root)?;
+
     let settings = load_config()?;
+    let project_config = load_project_config(root.as_path())?;
+
+    let search_engine = {
+        let search_engine_value = cli
+            .search_engine
+            .as_deref()
+            .or_else(|| settings.get("search_engine").map(|s| s.as_str()));
 
-    let search_engine = FzfSearchEngine::default();
+        match search_engine_value
+            .map(|value| value.to_lowercase())
+            .as_deref()
+        {
+            None | Some("fzf") => ConfiguredSearchEngine::Fzf(FzfSearchEngine::default()),
+            Some("builtin") => ConfiguredSearchEngine::Builtin(BuiltinSearchEngine::default()),
+            Some("skim") => ConfiguredSearchEngine::Skim(SkimSearchEngine::default()),
+            Some(search_engine) => {
+                return Err(FztError::InvalidArgument(format!(
+                    "Invalid search engine `{}` option. Use 'fzf', 'builtin' or 'skim'.",
+                    search_engine
+                )));
+            }
+        }
+    };
 
-    let mode = if cli.all {
+    let mode = if let Some(Commands::Run { patterns, .. }) = &cli.command {
+        RunnerMode::Filter(
+            patterns
+                .iter()
+                .map(|pattern| relative_to_root(pattern, &working_dir, &root))
+                .collect(),
+        )
+    } else if let Some(Commands::Suite {
+        command: SuiteCommands::Run { name },
+    }) = &cli.command
+    {
+        RunnerMode::Suite(name.clone())
+    } else if cli.all {
         RunnerMode::All
     } else if cli.last {
         RunnerMode::Last
@@ -293,6 +589,7 @@ pub fn parse_cli() -> Result<Config, FztError> {
         let mode_value = cli
             .mode
             .as_deref()
+            .or(project_config.mode.as_deref())
             .or_else(|| settings.get("mode").map(|s| s.as_str()));
 
         match mode_value {
@@ -310,6 +607,7 @@ pub fn parse_cli() -> Result<Config, FztError> {
         let preview_value = cli
             .preview
             .as_deref()
+            .or(project_config.preview.as_deref())
             .or_else(|| settings.get("preview").map(|s| s.as_str()));
 
         match preview_value {
@@ -318,10 +616,11 @@ pub fn parse_cli() -> Result<Config, FztError> {
             Some("a" | "auto") => Some(match filter_mode {
                 FilterMode::Directory => Preview::Directory,
                 FilterMode::File => Preview::File,
+                FilterMode::Test | FilterMode::Append if cli.failed => Preview::Failure,
                 FilterMode::Test | FilterMode::RunTime | FilterMode::Append => Preview::Test,
             }),
             Some("s" | "select") => {
-                let selection = search_engine.select(&["file", "test", "directory"])?;
+                let selection = search_engine.select(&["file", "test", "directory", "failure"])?;
                 Some(parse_preview(selection.as_str())?)
             }
             Some(preview) => Some(parse_preview(preview)?),
@@ -347,6 +646,74 @@ pub fn parse_cli() -> Result<Config, FztError> {
         None
     };
 
+    let list = match &cli.command {
+        Some(Commands::List {
+            granularity,
+            format,
+        }) => Some(ListOptions {
+            granularity: SelectGranularity::from_str(granularity)
+                .map_err(FztError::InvalidArgument)?,
+            format: parse_list_format(format)?,
+        }),
+        _ => None,
+    };
+
+    let suite = match &cli.command {
+        Some(Commands::Suite { command }) => match command {
+            SuiteCommands::List => Some(SuiteCommand::List),
+            SuiteCommands::Edit { name } => Some(SuiteCommand::Edit(name.clone())),
+            SuiteCommands::Delete { name } => Some(SuiteCommand::Delete(name.clone())),
+            SuiteCommands::Run { .. } => None,
+        },
+        _ => None,
+    };
+
+    let portable = match &cli.command {
+        Some(Commands::Export { file }) => Some(PortableCommand::Export(
+            file.as_ref().map(|file| working_dir.join(file)),
+        )),
+        Some(Commands::Import { file }) => Some(PortableCommand::Import(working_dir.join(file))),
+        _ => None,
+    };
+
+    if let Some(name) = cli.save.as_ref() {
+        validate_suite_name(name)?;
+    }
+
+    let repeat = if cli.repeat.is_some() || cli.until_fail {
+        if cli.watch || cli.covered {
+            return Err(FztError::InvalidArgument(
+                "'--repeat' and '--until-fail' can not be used with '--watch' or '--covered'."
+                    .to_string(),
+            ));
+        }
+        Some(RepeatOptions {
+            count: cli.repeat,
+            until_fail: cli.until_fail,
+        })
+    } else {
+        None
+    };
+
+    let flaky = match &cli.command {
+        Some(Commands::Flaky { limit }) => Some(FlakyOptions { limit: *limit }),
+        _ => None,
+    };
+
+    let dry_run = match cli.dry_run.as_deref() {
+        None => None,
+        Some("script") => Some(DryRun::Script),
+        Some(_) => Some(DryRun::Plain),
+    };
+
+    let update_history = dry_run.is_none()
+        && match &cli.command {
+            Some(Commands::Run { record, .. }) => *record,
+            // Suites are already saved, running them does not pollute the history
+            Some(Commands::Suite { .. }) => false,
+            _ => true,
+        };
+
     let language = match cli.command {
         Some(Commands::Python { parser, runtime }) => Language::Python { parser, runtime },
         Some(Commands::Java {
@@ -357,29 +724,94 @@ pub fn parse_cli() -> Result<Config, FztError> {
             runtime,
         },
         Some(Commands::Rust { runtime }) => Language::Rust { runtime },
-        None => get_default(project_hash()?.as_str())?,
+        Some(
+            Commands::List { .. }
+            | Commands::Run { .. }
+            | Commands::Cache { .. }
+            | Commands::Suite { .. }
+            | Commands::Export { .. }
+            | Commands::Import { .. }
+            | Commands::Flaky { .. }
+            | Commands::Preview { .. },
+        )
+        | None => match project_config.language()? {
+            Some(language) => language,
+            None => get_default(project_hash()?.as_str(), root.as_path())?,
+        },
     };
 
-    let runner_config = RunnerConfig::new(
-        cli.clear_cache,
-        cli.verbose,
-        cli.clear_history,
+    if matches!(mode, RunnerMode::Filter(_)) && matches!(filter_mode, FilterMode::Append) {
+        return Err(FztError::InvalidArgument(
+            "`run` does not support the 'append' mode.".to_string(),
+        ));
+    }
+
+    let runtime_args = if runtime_args.is_empty() {
+        project_config.runtime_args.clone()
+    } else {
+        runtime_args
+    };
+
+    let mut runtime_config = project_config.runtime_config();
+    runtime_config.dry_run = dry_run;
+    if cli.timeout.is_some() {
+        runtime_config.timeout = cli.timeout;
+    }
+    if cli.batch_timeout.is_some() {
+        runtime_config.batch_timeout = cli.batch_timeout;
+    }
+    // A timeout of 0 would kill every run right away
+    if runtime_config.timeout == Some(0) || runtime_config.batch_timeout == Some(0) {
+        return Err(FztError::InvalidArgument(format!(
+            "Invalid timeout in {}. Timeouts are at least 1 second.",
+            PROJECT_CONFIG_FILE
+        )));
+    }
+
+    let runner_config = RunnerConfig {
+        clear_cache: cli.clear_cache,
+        verbose: cli.verbose,
+        clear_history: cli.clear_history,
         runtime_args,
         mode,
         preview,
         filter_mode,
-        cli.query,
+        query: cli.query,
         debugger,
-        cli.failed,
-        true,
+        run_failed: cli.failed,
+        update_history,
         language,
         search_engine,
-        cli.covered,
-    );
+        covered: cli.covered,
+        runtime_config,
+        ignored_paths: project_config.ignore.clone(),
+        save_suite: cli.save,
+        retries: cli.retries,
+        skip_flaky: cli.skip_flaky,
+        repeat,
+    };
 
     Ok(Config {
         runner_config,
         default: cli.default,
         watch: cli.watch,
+        list,
+        suite,
+        portable,
+        flaky,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_at_least_once() {
+        let parse =
+            |count: &str| configure_commands().try_get_matches_from(["fzt", "--repeat", count]);
+        assert!(parse("0").is_err());
+        let matches = parse("2").unwrap();
+        assert_eq!(Cli::from_arg_matches(&matches).unwrap().repeat, Some(2));
+    }
+}
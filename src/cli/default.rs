@@ -1,18 +1,108 @@
+use std::{path::Path, process::Command};
+
+use colored::Colorize;
+
 use crate::{
     cache::manager::LocalCacheManager,
     errors::FztError,
     runner::{MetaData, RunnerName, config::Language},
+    search_engine::{SearchEngine, fzf::FzfSearchEngine},
 };
 
-pub fn get_default(project_id: &str) -> Result<Language, FztError> {
-    let reader = LocalCacheManager::get_meta(project_id)?;
-    let meta_data: MetaData = match reader {
-        Some(reader) => serde_json::from_reader(reader)?,
+const NEXTEST_CONFIG: &str = ".config/nextest.toml";
+const PYTHON_MARKERS: [&str; 3] = ["pyproject.toml", "pytest.ini", "conftest.py"];
+const GRADLE_MARKERS: [&str; 3] = ["build.gradle", "build.gradle.kts", "gradlew"];
+
+fn nextest_installed() -> bool {
+    Command::new("cargo")
+        .args(["nextest", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Guess the language and runtime of the project from the files in its root
+fn detect_language(root: &Path, nextest_installed: bool) -> Option<Language> {
+    if root.join("Cargo.toml").exists() {
+        if root.join(NEXTEST_CONFIG).exists() && !nextest_installed {
+            eprintln!(
+                "{}",
+                format!(
+                    "Project configures nextest ({NEXTEST_CONFIG}), but cargo-nextest is not installed. Falling back to cargo."
+                )
+                .yellow()
+            );
+        }
+        let runtime = if nextest_installed {
+            "nextest"
+        } else {
+            "cargo"
+        };
+        return Some(Language::Rust {
+            runtime: runtime.to_string(),
+        });
+    }
+    if PYTHON_MARKERS
+        .iter()
+        .any(|marker| root.join(marker).exists())
+    {
+        return Some(Language::Python {
+            parser: "rustpython".to_string(),
+            runtime: "pytest".to_string(),
+        });
+    }
+    if GRADLE_MARKERS
+        .iter()
+        .any(|marker| root.join(marker).exists())
+    {
+        return Some(Language::Java {
+            test_framework: "junit5".to_string(),
+            runtime: "gradle".to_string(),
+        });
+    }
+    None
+}
+
+fn language_meta_data(language: &Language) -> MetaData {
+    let (runner_name, runtime) = match language {
+        Language::Python { parser, runtime } if parser == "pytest" => {
+            (RunnerName::PytestRunner, runtime)
+        }
+        Language::Python { runtime, .. } => (RunnerName::RustPythonRunner, runtime),
+        Language::Java { runtime, .. } => (RunnerName::JavaJunit5Runner, runtime),
+        Language::Rust { runtime } if runtime == "nextest" => {
+            (RunnerName::RustNextestRunner, runtime)
+        }
+        Language::Rust { runtime } => (RunnerName::RustCargoRunner, runtime),
+    };
+    MetaData {
+        runner_name,
+        search_engine: FzfSearchEngine::default().name(),
+        runtime: runtime.clone(),
+    }
+}
+
+pub fn get_default(project_id: &str, root: &Path) -> Result<Language, FztError> {
+    let meta_data: MetaData = match LocalCacheManager::get_meta(project_id)? {
+        Some(meta_data) => meta_data,
         None => {
-            return Err(FztError::GeneralParsingError(
-                "Metadata not found. Did you initialize the project `fzt -d <LANGUAGE>` ?"
-                    .to_string(),
-            ));
+            let language = detect_language(root, nextest_installed()).ok_or(
+                FztError::GeneralParsingError(
+                    "Metadata not found and the project type could not be detected. Did you initialize the project `fzt --default <LANGUAGE>` ?"
+                        .to_string(),
+                ),
+            )?;
+            LocalCacheManager::save_meta(
+                project_id,
+                serde_json::to_string(&language_meta_data(&language))?.as_str(),
+            )?;
+            eprintln!(
+                "{}",
+                format!(
+                    "No default runner set, detected `{language}`. Saved it as project default, change it with `fzt --default <LANGUAGE>`."
+                )
+                .yellow()
+            );
+            return Ok(language);
         }
     };
 
@@ -37,3 +127,56 @@ pub fn get_default(project_id: &str) -> Result<Language, FztError> {
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn detect_rust() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert!(matches!(
+            detect_language(dir.path(), true),
+            Some(Language::Rust { runtime }) if runtime == "nextest"
+        ));
+        assert!(matches!(
+            detect_language(dir.path(), false),
+            Some(Language::Rust { runtime }) if runtime == "cargo"
+        ));
+    }
+
+    #[test]
+    fn detect_python() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("conftest.py"), "").unwrap();
+        let language = detect_language(dir.path(), true).unwrap();
+        assert!(matches!(
+            &language,
+            Language::Python { parser, runtime } if parser == "rustpython" && runtime == "pytest"
+        ));
+        assert!(matches!(
+            language_meta_data(&language).runner_name,
+            RunnerName::RustPythonRunner
+        ));
+    }
+
+    #[test]
+    fn detect_java() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("gradlew"), "").unwrap();
+        assert!(matches!(
+            detect_language(dir.path(), false),
+            Some(Language::Java { runtime, .. }) if runtime == "gradle"
+        ));
+    }
+
+    #[test]
+    fn detect_nothing() {
+        let dir = tempdir().unwrap();
+        assert!(detect_language(dir.path(), true).is_none());
+    }
+}
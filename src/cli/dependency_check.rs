@@ -125,36 +125,13 @@ fn colorize_table_output(table_str: &str) -> String {
 }
 
 pub fn check_dependencies() {
-    let dependencies = vec![
-        Dependency {
-            name: "fzf",
-            check_command: "which",
-            check_args: &["fzf"],
-            dep_type: DependencyType::Required,
-            description: "Fuzzy finder",
-        },
-        Dependency {
-            name: "expect",
-            check_command: "which",
-            check_args: &["expect"],
-            dep_type: DependencyType::Required,
-            description: "Automation tool",
-        },
-        Dependency {
-            name: "bat",
-            check_command: "which",
-            check_args: &["bat"],
-            dep_type: DependencyType::Required,
-            description: "File preview",
-        },
-        Dependency {
-            name: "ripgrep",
-            check_command: "which",
-            check_args: &["rg"],
-            dep_type: DependencyType::Required,
-            description: "Search tool",
-        },
-    ];
+    let dependencies = vec![Dependency {
+        name: "fzf",
+        check_command: "which",
+        check_args: &["fzf"],
+        dep_type: DependencyType::Required,
+        description: "Fuzzy finder (not needed with `--search-engine builtin` or `skim`)",
+    }];
 
     println!("\n{}\n", "FuzzyTestFinder Dependency Check".bold());
 
@@ -1,13 +1,25 @@
-use crate::{runner::config::RunnerConfig, search_engine::fzf::FzfSearchEngine};
+use crate::{
+    runner::{
+        config::RunnerConfig, flaky::FlakyOptions, list::ListOptions, portable::PortableCommand,
+        suite::SuiteCommand,
+    },
+    search_engine::ConfiguredSearchEngine,
+};
 
+mod cache_command;
 pub mod cli_parser;
 mod default;
 mod dependency_check;
+mod project_config;
 mod settings;
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub runner_config: RunnerConfig<FzfSearchEngine>,
+    pub runner_config: RunnerConfig<ConfiguredSearchEngine>,
     pub default: bool,
     pub watch: bool,
+    pub list: Option<ListOptions>,
+    pub suite: Option<SuiteCommand>,
+    pub portable: Option<PortableCommand>,
+    pub flaky: Option<FlakyOptions>,
 }
@@ -0,0 +1,153 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{errors::FztError, runner::config::Language, runtime::RuntimeConfig};
+
+pub const PROJECT_CONFIG_FILE: &str = ".fzt.toml";
+
+/// Versioned, per-repository settings read from `.fzt.toml` in the project root.
+/// Every key is optional, CLI flags take precedence over them.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub language: Option<String>,
+    pub runtime: Option<String>,
+    pub parser: Option<String>,
+    pub test_framework: Option<String>,
+    #[serde(default)]
+    pub runtime_args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub threads: Option<usize>,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    pub preview: Option<String>,
+    pub mode: Option<String>,
+    pub timeout: Option<u64>,
+    pub batch_timeout: Option<u64>,
+}
+
+impl ProjectConfig {
+    pub fn language(&self) -> Result<Option<Language>, FztError> {
+        let Some(language) = self.language.as_ref() else {
+            return Ok(None);
+        };
+        let runtime = self.runtime.as_ref().map(|runtime| runtime.to_lowercase());
+        match language.to_lowercase().as_str() {
+            "rust" => Ok(Some(Language::Rust {
+                runtime: runtime.unwrap_or(String::from("cargo")),
+            })),
+            "python" => Ok(Some(Language::Python {
+                parser: self
+                    .parser
+                    .as_ref()
+                    .map(|parser| parser.to_lowercase())
+                    .unwrap_or(String::from("rustpython")),
+                runtime: runtime.unwrap_or(String::from("pytest")),
+            })),
+            "java" => Ok(Some(Language::Java {
+                test_framework: self
+                    .test_framework
+                    .as_ref()
+                    .map(|test_framework| test_framework.to_lowercase())
+                    .unwrap_or(String::from("junit5")),
+                runtime: runtime.unwrap_or(String::from("gradle")),
+            })),
+            _ => Err(FztError::InvalidArgument(format!(
+                "Invalid language `{}` in {}. Use 'rust', 'python' or 'java'.",
+                language, PROJECT_CONFIG_FILE
+            ))),
+        }
+    }
+
+    pub fn runtime_config(&self) -> RuntimeConfig {
+        RuntimeConfig {
+            envs: self.env.clone(),
+            number_threads: self.threads,
+            dry_run: None,
+            timeout: self.timeout,
+            batch_timeout: self.batch_timeout,
+        }
+    }
+}
+
+fn parse_project_config(content: &str) -> Result<ProjectConfig, FztError> {
+    toml::from_str(content).map_err(FztError::from)
+}
+
+/// Load `.fzt.toml` from the project root, an absent file yields the default config
+pub fn load_project_config(root: &Path) -> Result<ProjectConfig, FztError> {
+    let config_path = root.join(PROJECT_CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(ProjectConfig::default());
+    }
+    parse_project_config(fs::read_to_string(config_path)?.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_project_config() {
+        let content = r#"
+language = "rust"
+runtime = "nextest"
+runtime_args = ["--locked"]
+threads = 4
+ignore = ["vendor", "tests/slow/"]
+preview = "auto"
+mode = "file"
+timeout = 30
+batch_timeout = 600
+
+[env]
+RUST_BACKTRACE = "1"
+"#;
+        let config = parse_project_config(content).unwrap();
+
+        assert_eq!(
+            config,
+            ProjectConfig {
+                language: Some("rust".to_string()),
+                runtime: Some("nextest".to_string()),
+                parser: None,
+                test_framework: None,
+                runtime_args: vec!["--locked".to_string()],
+                env: HashMap::from([("RUST_BACKTRACE".to_string(), "1".to_string())]),
+                threads: Some(4),
+                ignore: vec!["vendor".to_string(), "tests/slow/".to_string()],
+                preview: Some("auto".to_string()),
+                mode: Some("file".to_string()),
+                timeout: Some(30),
+                batch_timeout: Some(600),
+            }
+        );
+        assert!(matches!(
+            config.language().unwrap(),
+            Some(Language::Rust { runtime }) if runtime == "nextest"
+        ));
+    }
+
+    #[test]
+    fn test_language_defaults() {
+        let config = parse_project_config("language = \"Python\"").unwrap();
+        assert!(matches!(
+            config.language().unwrap(),
+            Some(Language::Python { parser, runtime }) if parser == "rustpython" && runtime == "pytest"
+        ));
+
+        let config = parse_project_config("").unwrap();
+        assert!(config.language().unwrap().is_none());
+
+        let config = parse_project_config("language = \"go\"").unwrap();
+        assert!(config.language().is_err());
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        assert!(parse_project_config("langauge = \"rust\"").is_err());
+    }
+}
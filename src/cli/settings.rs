@@ -1,13 +1,11 @@
 use std::collections::HashMap;
-use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
-use std::process::Command;
 
 use home::home_dir;
 
-use crate::FztError;
+use crate::{FztError, utils::file::open_in_editor};
 
 const DEFAULT_CONFIG: &str = r#"# ==========================
 # The config file consists of simple key-value pairs,
@@ -21,12 +19,14 @@ const DEFAULT_CONFIG: &str = r#"# ==========================
 # key = value
 
 # Available settings
-# preview=file | f | test | t | directory | d | select | s | auto | a | none
+# preview=file | f | test | t | directory | d | failure | x | select | s | auto | a | none
 # mode=directory | file | test | runtime | append | s | select
+# search_engine=fzf | builtin | skim
 
 # Default settings (uncomment and modify as needed)
 # preview=auto
 # mode=test
+# search_engine=fzf
 "#;
 
 pub fn update_settings() -> Result<(), FztError> {
@@ -43,37 +43,6 @@ pub fn update_settings() -> Result<(), FztError> {
     Ok(())
 }
 
-/// Open a file in the user's preferred editor
-fn open_in_editor(path: &PathBuf) -> Result<(), FztError> {
-    // Try to get the editor from environment variables
-    let editor = env::var("VISUAL")
-        .or_else(|_| env::var("EDITOR"))
-        .unwrap_or_else(|_| {
-            // Fallback editors based on platform
-            if cfg!(target_os = "windows") {
-                "notepad".to_string()
-            } else if cfg!(target_os = "macos") {
-                "open".to_string()
-            } else {
-                // Unix/Linux - try common editors
-                "vim".to_string()
-            }
-        });
-
-    let status = Command::new(&editor).arg(path).status().map_err(|e| {
-        FztError::RuntimeError(format!("Failed to open editor '{}': {}", editor, e))
-    })?;
-
-    if !status.success() {
-        return Err(FztError::RuntimeError(format!(
-            "Editor '{}' exited with non-zero status",
-            editor
-        )));
-    }
-
-    Ok(())
-}
-
 /// Parse the config file and return a HashMap of key-value pairs
 fn parse_config(config_path: PathBuf) -> Result<HashMap<String, String>, FztError> {
     let file = File::open(config_path)?;
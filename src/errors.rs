@@ -13,6 +13,7 @@ pub enum FztError {
     DictionaryWalking(walkdir::Error),
     Regex(regex::Error),
     Json(serde_json::Error),
+    Toml(toml::de::Error),
     UserError(String),
     JavaParser(String),
     PythonParser(String),
@@ -37,6 +38,7 @@ impl Display for FztError {
             FztError::DictionaryWalking(error) => write!(f, "{}", error),
             FztError::Regex(error) => write!(f, "{}", error),
             FztError::Json(error) => write!(f, "{}", error),
+            FztError::Toml(error) => write!(f, "{}", error),
             FztError::UserError(error) => write!(f, "{}", error),
             FztError::JavaParser(error) => write!(f, "{}", error),
             FztError::PythonParser(error) => write!(f, "{}", error),
@@ -93,6 +95,12 @@ impl From<serde_json::Error> for FztError {
     }
 }
 
+impl From<toml::de::Error> for FztError {
+    fn from(value: toml::de::Error) -> Self {
+        Self::Toml(value)
+    }
+}
+
 impl From<syn::Error> for FztError {
     fn from(value: syn::Error) -> Self {
         Self::RustParser(value)
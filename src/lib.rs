@@ -25,15 +25,20 @@ pub use runner::rust::get_rust_runner;
 pub use runtime::Debugger;
 pub use runtime::JavaDebugger;
 pub use runtime::PythonDebugger;
+pub use runtime::RunStatus;
 pub use runtime::Runtime;
+pub use runtime::RuntimeConfig;
 pub use runtime::RustDebugger;
 pub use runtime::java::gradle::GradleRuntime;
 pub use runtime::python::pytest::PytestRuntime;
 pub use runtime::rust::cargo::runtime::CargoRuntime;
 pub use runtime::rust::nextest::runtime::NextestRuntime;
 
+pub use search_engine::ConfiguredSearchEngine;
 pub use search_engine::SearchEngine;
+pub use search_engine::builtin::BuiltinSearchEngine;
 pub use search_engine::fzf::FzfSearchEngine;
+pub use search_engine::skim::SkimSearchEngine;
 
 pub use tests::Test;
 pub use tests::Tests;
@@ -1,11 +1,14 @@
+use std::process::ExitCode;
+
 use fzt::{
+    RunStatus,
     cache::{helper::project_hash, manager::LocalCacheManager},
     cli::cli_parser::parse_cli,
     errors::FztError,
     watcher::local::watch,
 };
 
-fn main() -> Result<(), FztError> {
+fn run() -> Result<RunStatus, FztError> {
     let config = parse_cli()?;
     let default = config.default;
     let watch_change = config.watch;
@@ -13,9 +16,36 @@ fn main() -> Result<(), FztError> {
     if default {
         LocalCacheManager::save_meta(project_hash()?.as_str(), runner.meta_data()?.as_str())?;
     }
+    if let Some(list) = config.list {
+        runner.list(&list)?;
+        return Ok(RunStatus::Passed);
+    }
+    if let Some(suite) = config.suite {
+        runner.suite(&suite)?;
+        return Ok(RunStatus::Passed);
+    }
+    if let Some(portable) = config.portable {
+        runner.portable(&portable)?;
+        return Ok(RunStatus::Passed);
+    }
+    if let Some(flaky) = config.flaky {
+        runner.flaky(&flaky)?;
+        return Ok(RunStatus::Passed);
+    }
     if watch_change {
-        watch(config.runner_config)
+        watch(config.runner_config)?;
+        Ok(RunStatus::Passed)
     } else {
         runner.run(None)
     }
 }
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(status) => ExitCode::from(status.exit_code()),
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::from(RunStatus::RuntimeError.exit_code())
+        }
+    }
+}
@@ -1,13 +1,19 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     cache::{helper::project_hash, manager::LocalCacheManager},
     errors::FztError,
-    runtime::Debugger,
+    runtime::{Debugger, RuntimeConfig},
     search_engine::SearchEngine,
+    tests::test_provider::SelectGranularity,
 };
 
-use super::{Runner, java::get_java_runner, python::get_python_runner, rust::get_rust_runner};
+use super::{
+    Runner, java::get_java_runner, python::get_python_runner, repeat::RepeatOptions,
+    rust::get_rust_runner,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum RunnerMode {
@@ -15,6 +21,10 @@ pub enum RunnerMode {
     Last,
     History,
     Select,
+    // Select all items matching one of the patterns without the search engine
+    Filter(Vec<String>),
+    // Run the suite saved under the name
+    Suite(String),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -22,6 +32,8 @@ pub enum Preview {
     File,
     Test,
     Directory,
+    // Output of the last failure of the test
+    Failure,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,6 +45,17 @@ pub enum FilterMode {
     Append,
 }
 
+impl From<&SelectGranularity> for FilterMode {
+    fn from(value: &SelectGranularity) -> Self {
+        match value {
+            SelectGranularity::Test => FilterMode::Test,
+            SelectGranularity::File => FilterMode::File,
+            SelectGranularity::Directory => FilterMode::Directory,
+            SelectGranularity::RunTime => FilterMode::RunTime,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum Language {
     Python {
@@ -48,6 +71,19 @@ pub enum Language {
     },
 }
 
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Language::Python { parser, runtime } => write!(f, "python {} {}", parser, runtime),
+            Language::Java {
+                test_framework,
+                runtime,
+            } => write!(f, "java {} {}", test_framework, runtime),
+            Language::Rust { runtime } => write!(f, "rust {}", runtime),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RunnerConfig<SE: SearchEngine + 'static> {
     pub clear_cache: bool,
@@ -64,43 +100,15 @@ pub struct RunnerConfig<SE: SearchEngine + 'static> {
     pub language: Language,
     pub search_engine: SE,
     pub covered: bool,
+    pub runtime_config: RuntimeConfig,
+    pub ignored_paths: Vec<String>,
+    pub save_suite: Option<String>,
+    pub retries: usize,
+    pub skip_flaky: bool,
+    pub repeat: Option<RepeatOptions>,
 }
 
 impl<SE: SearchEngine> RunnerConfig<SE> {
-    pub fn new(
-        clear_cache: bool,
-        verbose: bool,
-        clear_history: bool,
-        runtime_args: Vec<String>,
-        mode: RunnerMode,
-        preview: Option<Preview>,
-        filter_mode: FilterMode,
-        query: Option<String>,
-        debugger: Option<Debugger>,
-        run_failed: bool,
-        update_history: bool,
-        language: Language,
-        search_engine: SE,
-        covered: bool,
-    ) -> Self {
-        Self {
-            clear_cache,
-            verbose,
-            clear_history,
-            runtime_args,
-            mode,
-            preview,
-            filter_mode,
-            query,
-            debugger,
-            run_failed,
-            update_history,
-            language,
-            search_engine,
-            covered,
-        }
-    }
-
     fn build_cache_manager(&self, project_id: &str) -> LocalCacheManager {
         if self.run_failed {
             LocalCacheManager::new_failed_tests(project_id)
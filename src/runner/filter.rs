@@ -0,0 +1,139 @@
+use colored::Colorize;
+use regex::Regex;
+
+use crate::errors::FztError;
+
+const REGEX_PREFIX: &str = "re:";
+
+/// Pattern used to select items without the search engine.
+///
+/// `re:<REGEX>` is matched as a regular expression, a pattern containing one of `*`, `?` or `[`
+/// as a glob (`*` does not cross `/`, `**` does) and every other pattern as substring.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self, FztError> {
+        if let Some(regex) = pattern.strip_prefix(REGEX_PREFIX) {
+            Ok(Pattern::Regex(Regex::new(regex)?))
+        } else if pattern.contains(['*', '?', '[']) {
+            Ok(Pattern::Regex(Regex::new(glob_to_regex(pattern).as_str())?))
+        } else {
+            Ok(Pattern::Substring(pattern.to_string()))
+        }
+    }
+
+    pub fn matches(&self, item: &str) -> bool {
+        match self {
+            Pattern::Substring(pattern) => item.contains(pattern.as_str()),
+            Pattern::Regex(regex) => regex.is_match(item),
+        }
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                regex.push('[');
+                for ch in chars.by_ref() {
+                    regex.push(ch);
+                    if ch == ']' {
+                        break;
+                    }
+                }
+            }
+            _ => regex.push_str(regex::escape(ch.to_string().as_str()).as_str()),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Returns all items matching at least one of the patterns
+pub fn filter_items(items: &[&str], patterns: &[String]) -> Result<Vec<String>, FztError> {
+    let patterns = patterns
+        .iter()
+        .map(|pattern| Ok((pattern, Pattern::parse(pattern)?)))
+        .collect::<Result<Vec<(&String, Pattern)>, FztError>>()?;
+    patterns
+        .iter()
+        .filter(|(_, pattern)| !items.iter().any(|item| pattern.matches(item)))
+        .for_each(|(raw, _)| {
+            println!(
+                "{}",
+                format!("[SKIPPED] pattern `{raw}` does not match any item.").yellow()
+            )
+        });
+    let mut selection: Vec<String> = items
+        .iter()
+        .filter(|item| patterns.iter().any(|(_, pattern)| pattern.matches(item)))
+        .map(|item| item.to_string())
+        .collect();
+    selection.sort();
+    Ok(selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const ITEMS: [&str; 4] = [
+        "tests/test_a.py::test_one",
+        "tests/test_a.py::test_two",
+        "tests/sub/test_b.py::test_three",
+        "src/lib.py::test_lib",
+    ];
+
+    fn filter(patterns: &[&str]) -> Vec<String> {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        filter_items(&ITEMS, patterns.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn substring() {
+        assert_eq!(
+            filter(&["test_t"]),
+            vec![
+                "tests/sub/test_b.py::test_three".to_string(),
+                "tests/test_a.py::test_two".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn glob() {
+        assert_eq!(
+            filter(&["tests/*::test_one", "src/**"]),
+            vec![
+                "src/lib.py::test_lib".to_string(),
+                "tests/test_a.py::test_one".to_string(),
+            ]
+        );
+        assert_eq!(filter(&["tests/**/test_[b]*"]).len(), 1);
+    }
+
+    #[test]
+    fn regex() {
+        assert_eq!(
+            filter(&["re:test_(one|three)$"]),
+            vec![
+                "tests/sub/test_b.py::test_three".to_string(),
+                "tests/test_a.py::test_one".to_string(),
+            ]
+        );
+        assert!(Pattern::parse("re:(").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+};
+
+use colored::Colorize;
+
+use crate::{
+    cache::types::TestRecord,
+    runtime::{FailedTest, RunStatus, RuntimeOutput, TestResult, TestStatus},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlakyOptions {
+    // Number of tests shown, all if not set
+    pub limit: Option<usize>,
+}
+
+// Tests the retry reported a result for
+fn retried(retry: &RuntimeOutput) -> HashSet<&str> {
+    retry
+        .test_results
+        .iter()
+        .map(|result| result.name.as_str())
+        .chain(
+            retry
+                .failed_tests
+                .iter()
+                .map(|failed_test| failed_test.name.as_str()),
+        )
+        .collect()
+}
+
+/// Failures of the run that merging the retry replaces, they are recorded before the merge
+/// so every failed attempt counts
+pub fn replaced_failures(
+    output: &RuntimeOutput,
+    retry: &RuntimeOutput,
+) -> (Vec<TestResult>, Vec<FailedTest>) {
+    let retried = retried(retry);
+    (
+        output
+            .test_results
+            .iter()
+            .filter(|result| result.status == TestStatus::Failed)
+            .filter(|result| retried.contains(result.name.as_str()))
+            .cloned()
+            .collect(),
+        output
+            .failed_tests
+            .iter()
+            .filter(|failed_test| retried.contains(failed_test.name.as_str()))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Merge the output of a retry of the failed tests into the output of the run.
+/// Returns the tests that passed on the retry, they are flaky and not failed anymore.
+pub fn merge_retry(output: &mut RuntimeOutput, retry: RuntimeOutput) -> Vec<String> {
+    let flaky: Vec<String> = retry
+        .test_results
+        .iter()
+        .filter(|result| result.status == TestStatus::Passed)
+        .filter(|result| {
+            output
+                .failed_tests
+                .iter()
+                .any(|failed_test| failed_test.name == result.name)
+        })
+        .map(|result| result.name.clone())
+        .collect();
+    let retried = retried(&retry);
+    // Tests the retry did not report keep the result of the run
+    output
+        .failed_tests
+        .retain(|failed_test| !retried.contains(failed_test.name.as_str()));
+    output
+        .test_results
+        .retain(|result| !retried.contains(result.name.as_str()));
+    output
+        .timed_out
+        .retain(|test| !retried.contains(test.as_str()));
+    output.failed_tests.extend(retry.failed_tests);
+    output.test_results.extend(retry.test_results);
+    output.timed_out.extend(retry.timed_out);
+    if output.status != RunStatus::RuntimeError {
+        output.status = if output.failed_tests.is_empty() {
+            retry.status
+        } else {
+            retry.status.max(RunStatus::TestsFailed)
+        };
+    }
+    flaky
+}
+
+pub fn print_flaky(flaky: &[String]) {
+    println!("\n{}:", "flaky".yellow().bold());
+    flaky.iter().for_each(|test| println!("    {}", test));
+}
+
+/// Tests that passed on a retry, the most flaky first
+pub fn format_flaky_report(records: &BTreeMap<String, TestRecord>, limit: Option<usize>) -> String {
+    let mut flaky: Vec<(&String, &TestRecord)> = records
+        .iter()
+        .filter(|(_, record)| record.flaky > 0)
+        .collect();
+    flaky.sort_by_key(|(_, record)| Reverse(record.flaky));
+    flaky
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(test, record)| format!("{:>4}x  {}\n", record.flaky, test))
+        .collect()
+}
+
+pub fn print_flaky_report(records: &BTreeMap<String, TestRecord>, options: &FlakyOptions) {
+    let report = format_flaky_report(records, options.limit);
+    if report.is_empty() {
+        println!("No flaky tests recorded. Retry failed tests with `fzt --retries <N>`.");
+    } else {
+        print!("{}", report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn retry_classifies_flaky_tests() {
+        let mut output = RuntimeOutput::new_empty();
+        output.status = RunStatus::TestsFailed;
+        output.failed_tests = vec![
+            FailedTest::new("test_a", "assert 1 == 2"),
+            FailedTest::new("test_b", "assert 2 == 3"),
+        ];
+        output.test_results = vec![
+            TestResult::new("test_a", TestStatus::Failed, Some(0.1)),
+            TestResult::new("test_b", TestStatus::Failed, Some(0.1)),
+            TestResult::new("test_c", TestStatus::Passed, Some(0.1)),
+        ];
+        let mut retry = RuntimeOutput::new_empty();
+        retry.status = RunStatus::TestsFailed;
+        retry.failed_tests = vec![FailedTest::new("test_b", "assert 2 == 4")];
+        retry.test_results = vec![
+            TestResult::new("test_a", TestStatus::Passed, Some(0.2)),
+            TestResult::new("test_b", TestStatus::Failed, Some(0.2)),
+        ];
+
+        assert_eq!(
+            replaced_failures(&output, &retry),
+            (
+                vec![
+                    TestResult::new("test_a", TestStatus::Failed, Some(0.1)),
+                    TestResult::new("test_b", TestStatus::Failed, Some(0.1)),
+                ],
+                vec![
+                    FailedTest::new("test_a", "assert 1 == 2"),
+                    FailedTest::new("test_b", "assert 2 == 3"),
+                ]
+            )
+        );
+        assert_eq!(merge_retry(&mut output, retry), vec!["test_a".to_string()]);
+        assert_eq!(output.status, RunStatus::TestsFailed);
+        assert_eq!(
+            output.failed_tests,
+            vec![FailedTest::new("test_b", "assert 2 == 4")]
+        );
+        assert_eq!(
+            output.test_results,
+            vec![
+                TestResult::new("test_c", TestStatus::Passed, Some(0.1)),
+                TestResult::new("test_a", TestStatus::Passed, Some(0.2)),
+                TestResult::new("test_b", TestStatus::Failed, Some(0.2)),
+            ]
+        );
+
+        let mut retry = RuntimeOutput::new_empty();
+        retry.test_results = vec![TestResult::new("test_b", TestStatus::Passed, Some(0.2))];
+        assert_eq!(merge_retry(&mut output, retry), vec!["test_b".to_string()]);
+        assert_eq!(output.status, RunStatus::Passed);
+        assert!(output.failed_tests.is_empty());
+    }
+}
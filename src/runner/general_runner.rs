@@ -1,25 +1,66 @@
-use std::{collections::HashMap, str::FromStr, sync::mpsc::Receiver};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+    sync::mpsc::Receiver,
+};
 
+use colored::Colorize;
 use serde::de::DeserializeOwned;
 
 use crate::{
-    cache::{Cache, manager::HistoryGranularity},
+    cache::{Cache, manager::HistoryGranularity, types::Suite},
     errors::FztError,
     runner::{MetaData, Runner, RunnerName},
-    runtime::Runtime,
-    search_engine::{Append, SearchEngine},
+    runtime::{FailedTest, RunStatus, Runtime, RuntimeOutput, TestResult},
+    search_engine::{Append, SearchEngine, TestSelection},
     tests::{
-        Tests,
+        Test, Tests,
         test_provider::{SelectGranularity, TestProvider},
     },
 };
 
 use super::{
     config::{FilterMode, Preview, RunnerConfig, RunnerMode},
+    filter::filter_items,
+    flaky::{FlakyOptions, merge_retry, print_flaky, print_flaky_report, replaced_failures},
     history_provider::HistoryProvider,
+    list::{ListOptions, list_items, print_items},
+    portable::{HISTORY_GRANULARITIES, Inventory, Portable, PortableCommand},
+    repeat::{RepeatOptions, Tally, repeated_tests, save_output},
+    suite::{SuiteCommand, edit_suite, print_suites, unknown_suite_error, validate_suite_name},
 };
 
-fn append_selection_to_preview(selection: &HashMap<SelectGranularity, Vec<String>>) -> String {
+// Selected items per granularity, only append selections contain more than one granularity
+type Selection = HashMap<SelectGranularity, Vec<String>>;
+
+// Runtime argument and name of a test reported by the runtime. Parametrized tests
+// are reported per parameter, e.g. `test_x[1]` for the test `test_x`
+fn resolve_reported<'a>(
+    names: &'a HashMap<String, String>,
+    reported: &str,
+) -> Option<(&'a String, &'a String)> {
+    names.get_key_value(reported).or_else(|| {
+        reported
+            .split_once('[')
+            .and_then(|(test, _)| names.get_key_value(test))
+    })
+}
+
+// Timeouts are listed apart from the failures reported by the runtime
+fn print_timed_out(timed_out: &[String], failed_tests: &[FailedTest]) {
+    println!("\n{}:", "timed out".red().bold());
+    timed_out.iter().for_each(|test| {
+        match failed_tests
+            .iter()
+            .find(|failed_test| &failed_test.name == test)
+        {
+            Some(failed_test) => println!("    {} ({})", test, failed_test.error_msg),
+            None => println!("    {}", test),
+        }
+    });
+}
+
+fn append_selection_to_preview(selection: &Selection) -> String {
     let mut preview = String::new();
     selection.iter().for_each(|(select, selected_items)| {
         preview.push_str(&format!("{}\n", select));
@@ -32,7 +73,7 @@ fn append_selection_to_preview(selection: &HashMap<SelectGranularity, Vec<String
     preview
 }
 
-fn parse_append_history(history: Vec<String>) -> HashMap<SelectGranularity, Vec<String>> {
+fn parse_append_history(history: Vec<String>) -> Selection {
     let mut selection = HashMap::new();
     history.into_iter().for_each(|test| {
         let mut parts = test.splitn(2, ' ');
@@ -87,13 +128,8 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests, CM: Cache + Clone> GeneralCacheRun
         }
     }
 
-    fn select_tests(
-        &mut self,
-        granularity: &SelectGranularity,
-        test_provider: &TestProvider,
-        query: &Option<String>,
-    ) -> Result<Vec<String>, FztError> {
-        let preview = if granularity == &SelectGranularity::Directory {
+    fn preview(&self, granularity: &SelectGranularity) -> Option<Preview> {
+        if granularity == &SelectGranularity::Directory {
             if self.config.preview.is_some() {
                 Some(Preview::Directory)
             } else {
@@ -101,73 +137,190 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests, CM: Cache + Clone> GeneralCacheRun
             }
         } else if granularity == &SelectGranularity::RunTime {
             None
+        } else if granularity == &SelectGranularity::File
+            && matches!(self.config.preview, Some(Preview::Failure))
+        {
+            // Failures are recorded per test
+            Some(Preview::File)
         } else {
             self.config.preview.clone()
-        };
-        Ok(self.config.search_engine.get_tests_to_run(
-            test_provider.select_option(granularity).as_slice(),
-            &preview,
-            query,
-        )?)
+        }
+    }
+
+    // Returns the granularity the items were selected in, it can be switched in the finder
+    fn select_tests(
+        &mut self,
+        granularity: &SelectGranularity,
+        test_provider: &TestProvider,
+        query: &Option<String>,
+    ) -> Result<(SelectGranularity, Vec<String>), FztError> {
+        let mut granularity = *granularity;
+        let mut query = query.clone();
+        loop {
+            // Results are recorded per test
+            let records = if granularity == SelectGranularity::Test {
+                self.cache_manager.test_records()?
+            } else {
+                BTreeMap::new()
+            };
+            match self.config.search_engine.get_tests_to_run(
+                test_provider.select_option(&granularity).as_slice(),
+                &self.preview(&granularity),
+                &query,
+                &records,
+                test_provider.test_lines(),
+            )? {
+                TestSelection::Items(selected_items) => return Ok((granularity, selected_items)),
+                TestSelection::Switch(next, current_query) => {
+                    granularity = next;
+                    query = Some(current_query);
+                }
+            }
+        }
+    }
+
+    // Test names by runtime argument
+    fn test_names(&self) -> HashMap<String, String> {
+        self.tests
+            .tests()
+            .iter()
+            .map(|test| (test.runtime_argument(), test.name()))
+            .collect()
+    }
+
+    // The runtime reports tests by their runtime argument, results are recorded by test name
+    fn record_test_results(
+        &self,
+        results: &[TestResult],
+        failed_tests: &[FailedTest],
+    ) -> Result<(), FztError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let names = self.test_names();
+        let results: Vec<TestResult> = results
+            .iter()
+            .filter_map(|result| {
+                resolve_reported(&names, &result.name)
+                    .map(|(_, name)| TestResult::new(name, result.status, result.duration))
+            })
+            .collect();
+        let failed_tests: Vec<FailedTest> = failed_tests
+            .iter()
+            .filter_map(|failed_test| {
+                resolve_reported(&names, &failed_test.name)
+                    .map(|(_, name)| FailedTest::new(name, &failed_test.error_msg))
+            })
+            .collect();
+        self.cache_manager
+            .record_test_results(results.as_slice(), failed_tests.as_slice())
     }
 
+    // Re-running the last selection counts as usage for the frecency ranking
+    fn last(&self, history_granularity: &HistoryGranularity) -> Result<Vec<String>, FztError> {
+        let last = self.history_provider.last(history_granularity)?;
+        if self.config.update_history {
+            self.history_provider
+                .update_history(history_granularity, last.as_slice())?;
+        }
+        Ok(last)
+    }
+
+    fn suite_selection(&self, name: &str) -> Result<Selection, FztError> {
+        let suites = self.cache_manager.suites()?;
+        let suite = suites
+            .get(name)
+            .ok_or_else(|| unknown_suite_error(name, &suites))?;
+        Ok(suite
+            .selection
+            .iter()
+            .map(|(select, selected_items)| (*select, selected_items.clone()))
+            .collect())
+    }
+
+    // Returns the granularity if it was switched in the finder
     fn get_tests_to_run(
         &mut self,
         query: &Option<String>,
         test_provider: &TestProvider,
         history_granularity: &HistoryGranularity,
         select_granularity: &SelectGranularity,
-    ) -> Result<Vec<String>, FztError> {
-        Ok(match self.config.mode {
-            RunnerMode::All => test_provider.all(select_granularity),
-            RunnerMode::Last => test_provider.runtime_arguments(
-                select_granularity,
-                self.history_provider.last(history_granularity)?.as_slice(),
-            ),
-            RunnerMode::History => test_provider.runtime_arguments(
-                select_granularity,
-                self.history_provider
-                    .history(history_granularity, &self.config.search_engine, query)?
-                    .as_slice(),
-            ),
+    ) -> Result<(Selection, Option<SelectGranularity>), FztError> {
+        let selected_items = match self.config.mode {
+            // Failed and covered providers resolve items with all tests,
+            // so the runtime arguments are taken from the provider itself
+            RunnerMode::All => {
+                return Ok((
+                    HashMap::from([(
+                        SelectGranularity::RunTime,
+                        test_provider.all(select_granularity),
+                    )]),
+                    None,
+                ));
+            }
+            RunnerMode::Last => self.last(history_granularity)?,
+            RunnerMode::History => self.history_provider.history(
+                history_granularity,
+                &self.config.search_engine,
+                query,
+                self.config.update_history,
+            )?,
             RunnerMode::Select => {
-                let selected_items = self.select_tests(select_granularity, test_provider, query)?;
+                let (granularity, selected_items) =
+                    self.select_tests(select_granularity, test_provider, query)?;
+                if self.config.update_history {
+                    self.history_provider.update_history(
+                        &HistoryGranularity::from(&FilterMode::from(&granularity)),
+                        selected_items.as_slice(),
+                    )?;
+                }
+                return Ok((
+                    HashMap::from([(granularity, selected_items)]),
+                    Some(granularity).filter(|granularity| granularity != select_granularity),
+                ));
+            }
+            RunnerMode::Filter(ref patterns) => {
+                let selected_items = filter_items(
+                    test_provider.select_option(select_granularity).as_slice(),
+                    patterns.as_slice(),
+                )?;
+                if selected_items.is_empty() {
+                    return Err(FztError::InvalidArgument(format!(
+                        "No {} item matches the patterns: {}",
+                        select_granularity,
+                        patterns.join(", ")
+                    )));
+                }
                 if self.config.update_history {
                     self.history_provider
                         .update_history(history_granularity, selected_items.as_slice())?;
                 }
-                test_provider.runtime_arguments(select_granularity, selected_items.as_slice())
+                selected_items
             }
-        })
+            RunnerMode::Suite(ref name) => return Ok((self.suite_selection(name)?, None)),
+        };
+        Ok((HashMap::from([(*select_granularity, selected_items)]), None))
     }
 
     fn select_append(
         &mut self,
         query: &Option<String>,
         test_provider: &TestProvider,
-    ) -> Result<Vec<String>, FztError> {
+    ) -> Result<Selection, FztError> {
         Ok(match self.config.mode {
-            RunnerMode::All => test_provider.all(&SelectGranularity::RunTime),
-            RunnerMode::Last => {
-                parse_append_history(self.history_provider.last(&HistoryGranularity::Append)?)
-                    .iter()
-                    .flat_map(|(select, selected_items)| {
-                        test_provider.runtime_arguments(select, selected_items.as_slice())
-                    })
-                    .collect()
-            }
+            RunnerMode::All => HashMap::from([(
+                SelectGranularity::RunTime,
+                test_provider.all(&SelectGranularity::RunTime),
+            )]),
+            RunnerMode::Last => parse_append_history(self.last(&HistoryGranularity::Append)?),
             RunnerMode::History => {
                 let history = self.history_provider.history(
                     &HistoryGranularity::Append,
                     &self.config.search_engine,
                     query,
+                    self.config.update_history,
                 )?;
                 parse_append_history(history)
-                    .iter()
-                    .flat_map(|(select, selected_items)| {
-                        test_provider.runtime_arguments(select, selected_items.as_slice())
-                    })
-                    .collect()
             }
             RunnerMode::Select => {
                 let mut selection = HashMap::new();
@@ -179,9 +332,8 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests, CM: Cache + Clone> GeneralCacheRun
                     if append == Append::Done {
                         break;
                     }
-                    let select_granularity = SelectGranularity::from(append);
-                    let mut selected_items =
-                        self.select_tests(&select_granularity, test_provider, query)?;
+                    let (select_granularity, mut selected_items) =
+                        self.select_tests(&SelectGranularity::from(append), test_provider, query)?;
                     selection
                         .entry(select_granularity)
                         .or_insert(vec![])
@@ -202,31 +354,44 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests, CM: Cache + Clone> GeneralCacheRun
                         .update_history(&HistoryGranularity::Append, history_update.as_slice())?;
                 }
                 selection
-                    .iter()
-                    .flat_map(|(select, selected_items)| {
-                        test_provider.runtime_arguments(select, selected_items.as_slice())
-                    })
-                    .collect()
             }
+            RunnerMode::Filter(_) => {
+                return Err(FztError::InvalidArgument(
+                    "Patterns can not be used in append mode.".to_string(),
+                ));
+            }
+            RunnerMode::Suite(ref name) => self.suite_selection(name)?,
         })
     }
-}
 
-impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clone> Runner
-    for GeneralCacheRunner<SE, RT, T, CM>
-{
-    fn run(&mut self, receiver: Option<Receiver<String>>) -> Result<(), FztError> {
-        if self.config.clear_cache || self.config.clear_history {
-            if self.config.clear_cache {
-                self.cache_manager.clear_cache()?;
-            }
-            if self.config.clear_history {
-                self.cache_manager.clear_history()?;
-            }
+    fn save_suite(&self, name: &str, selection: &Selection) -> Result<(), FztError> {
+        let suite = Suite::new(
+            selection
+                .iter()
+                .filter(|(_, selected_items)| !selected_items.is_empty())
+                .map(|(select, selected_items)| (*select, selected_items.clone()))
+                .collect(),
+        );
+        if suite.is_empty() {
             return Ok(());
         }
-        if let Some(reader) = self.cache_manager.get_entry()? {
-            self.tests = serde_json::from_reader(reader)?;
+        self.cache_manager.save_suite(name, &suite)?;
+        println!("Saved {} items as suite `{}`", suite.len(), name);
+        Ok(())
+    }
+}
+
+impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clone>
+    GeneralCacheRunner<SE, RT, T, CM>
+{
+    fn load_tests(&mut self) -> Result<(), FztError> {
+        // An entry that can not be deserialized anymore gets rebuilt
+        let cached_tests = self
+            .cache_manager
+            .get_entry()?
+            .and_then(|entry| serde_json::from_str::<T>(entry.as_str()).ok());
+        if let Some(tests) = cached_tests {
+            self.tests = tests;
             if self.tests.update()? {
                 self.cache_manager
                     .add_entry(self.tests.to_json()?.as_str())?;
@@ -236,16 +401,188 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clo
             self.cache_manager
                 .add_entry(self.tests.to_json()?.as_str())?;
         }
+        Ok(())
+    }
 
-        let test_provider = if self.config.run_failed {
-            TestProvider::new_failed(&self.tests)
+    // Re-run the failed tests, tests passing on a retry are returned as flaky
+    fn retry_failed(&self, runtime_output: &mut RuntimeOutput) -> Result<Vec<String>, FztError> {
+        let mut flaky = vec![];
+        // Retries under the debugger would stop at every failure again
+        if self.config.debugger.is_some() {
+            return Ok(flaky);
+        }
+        let names = self.test_names();
+        for attempt in 1..=self.config.retries {
+            if runtime_output.failed_tests.is_empty() {
+                break;
+            }
+            // Failed tests are retried with their runtime argument
+            let mut failed_tests: Vec<String> = runtime_output
+                .failed_tests
+                .iter()
+                .map(|failed_test| {
+                    resolve_reported(&names, &failed_test.name)
+                        .map_or(failed_test.name.clone(), |(argument, _)| argument.clone())
+                })
+                .collect();
+            failed_tests.sort();
+            failed_tests.dedup();
+            println!(
+                "\n{}",
+                format!(
+                    "Retrying {} failed tests ({}/{})",
+                    failed_tests.len(),
+                    attempt,
+                    self.config.retries
+                )
+                .yellow()
+                .bold()
+            );
+            let retry = self.runtime.run_tests(
+                failed_tests,
+                self.config.verbose,
+                self.config.runtime_args.as_slice(),
+                &None,
+                None,
+                false,
+            )?;
+            let (results, failures) = replaced_failures(runtime_output, &retry);
+            self.record_test_results(results.as_slice(), failures.as_slice())?;
+            flaky.extend(merge_retry(runtime_output, retry));
+        }
+        Ok(flaky)
+    }
+
+    fn record_outcome(&self, status: RunStatus) -> Result<(), FztError> {
+        if self.config.update_history && self.config.mode != RunnerMode::All {
+            self.history_provider
+                .record_outcome(&HistoryGranularity::from(&self.config.filter_mode), status)?;
+        }
+        Ok(())
+    }
+
+    // Run the tests repeatedly, copies run at the same time if the runtime allows it
+    fn repeat(
+        &mut self,
+        tests: Vec<String>,
+        options: &RepeatOptions,
+    ) -> Result<RunStatus, FztError> {
+        let parallel = self.runtime.parallel_repetitions().max(1);
+        let mut tally = Tally::default();
+        let mut status = RunStatus::Passed;
+        let mut failed_tests: Vec<FailedTest> = vec![];
+        let mut done = 0;
+        while options.count.is_none_or(|count| done < count) {
+            let repetitions = options
+                .count
+                .map_or(parallel, |count| parallel.min(count - done));
+            let repetition = if repetitions == 1 {
+                (done + 1).to_string()
+            } else {
+                format!("{}-{}", done + 1, done + repetitions)
+            };
+            let total = options
+                .count
+                .map(|count| format!("/{}", count))
+                .unwrap_or_default();
+            println!("\n{}", format!("Repetition {}{}", repetition, total).bold());
+            let runtime_output = self.runtime.run_tests(
+                repeated_tests(tests.as_slice(), repetitions),
+                self.config.verbose,
+                self.config.runtime_args.as_slice(),
+                &None,
+                None,
+                false,
+            )?;
+            done += repetitions;
+            tally.add(runtime_output.test_results.as_slice());
+            self.record_test_results(
+                runtime_output.test_results.as_slice(),
+                runtime_output.failed_tests.as_slice(),
+            )?;
+            status = status.max(runtime_output.status);
+            if runtime_output.status == RunStatus::Passed {
+                continue;
+            }
+            // Each failed item is one failed repetition of its test
+            if !runtime_output.failed_output.is_empty() {
+                for (test, output) in runtime_output.failed_output.iter() {
+                    let path = save_output(test, output)?;
+                    println!(
+                        "{}",
+                        format!("Output of failed {} saved to {}", test, path.display()).red()
+                    );
+                }
+            } else if let Some(output) = runtime_output.output.as_ref() {
+                let path = save_output(repetition.as_str(), output)?;
+                println!(
+                    "{}",
+                    format!(
+                        "Output of repetition {} saved to {}",
+                        repetition,
+                        path.display()
+                    )
+                    .red()
+                );
+            }
+            failed_tests.extend(runtime_output.failed_tests);
+            // Every following repetition would fail to run as well
+            if options.until_fail || runtime_output.status == RunStatus::RuntimeError {
+                break;
+            }
+        }
+        tally.print(done);
+        self.record_outcome(status)?;
+        if !self.config.run_failed && self.tests.update_failed(failed_tests.as_slice()) {
+            self.cache_manager
+                .add_entry(self.tests.to_json()?.as_str())?;
+        }
+        Ok(status)
+    }
+
+    fn test_provider(&self) -> Result<TestProvider, FztError> {
+        let ignored_paths = self.config.ignored_paths.as_slice();
+        if self.config.run_failed {
+            let excluded: HashSet<String> = if self.config.skip_flaky {
+                self.cache_manager
+                    .test_records()?
+                    .into_iter()
+                    .filter(|(_, record)| record.flaky > 0)
+                    .map(|(name, _)| name)
+                    .collect()
+            } else {
+                HashSet::new()
+            };
+            Ok(TestProvider::new_failed(
+                &self.tests,
+                ignored_paths,
+                &excluded,
+            ))
         } else if self.config.covered {
-            TestProvider::new_covered_tests(&self.tests)
+            Ok(TestProvider::new_covered_tests(&self.tests, ignored_paths))
         } else {
-            TestProvider::new(&self.tests)
-        };
+            Ok(TestProvider::new(&self.tests, ignored_paths))
+        }
+    }
+}
+
+impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clone> Runner
+    for GeneralCacheRunner<SE, RT, T, CM>
+{
+    fn run(&mut self, receiver: Option<Receiver<String>>) -> Result<RunStatus, FztError> {
+        if self.config.clear_cache || self.config.clear_history {
+            if self.config.clear_cache {
+                self.cache_manager.clear_cache()?;
+            }
+            if self.config.clear_history {
+                self.cache_manager.clear_history()?;
+            }
+            return Ok(RunStatus::Passed);
+        }
+        self.load_tests()?;
+        let test_provider = self.test_provider()?;
 
-        let tests_to_run: Vec<String> = match self.config.filter_mode {
+        let (selection, switched) = match self.config.filter_mode {
             FilterMode::Test => self.get_tests_to_run(
                 &self.config.query.clone(),
                 &test_provider,
@@ -270,11 +607,33 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clo
                 &HistoryGranularity::RunTime,
                 &SelectGranularity::RunTime,
             )?,
-            FilterMode::Append => self.select_append(&self.config.query.clone(), &test_provider)?,
+            FilterMode::Append => (
+                self.select_append(&self.config.query.clone(), &test_provider)?,
+                None,
+            ),
         };
+        // Switching the granularity in the finder switches the mode
+        if let Some(granularity) = switched {
+            self.config.filter_mode = FilterMode::from(&granularity);
+        }
+        if let Some(name) = self.config.save_suite.as_ref() {
+            self.save_suite(name, &selection)?;
+        }
+        // Items that do not exist anymore are skipped with a warning
+        let tests_to_run: Vec<String> = selection
+            .iter()
+            .flat_map(|(select, selected_items)| {
+                test_provider.runtime_arguments(select, selected_items.as_slice())
+            })
+            .collect();
         drop(test_provider);
         if !tests_to_run.is_empty() {
-            let runtime_output = self.runtime.run_tests(
+            if let Some(options) = self.config.repeat.clone()
+                && self.config.runtime_config.dry_run.is_none()
+            {
+                return self.repeat(tests_to_run, &options);
+            }
+            let mut runtime_output = self.runtime.run_tests(
                 tests_to_run,
                 self.config.verbose,
                 &self.config.runtime_args.as_slice(),
@@ -282,6 +641,34 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clo
                 receiver,
                 self.config.covered,
             )?;
+            if self.config.runtime_config.dry_run.is_some() {
+                return Ok(runtime_output.status);
+            }
+            let flaky = self.retry_failed(&mut runtime_output)?;
+            if !flaky.is_empty() {
+                print_flaky(flaky.as_slice());
+            }
+            if !runtime_output.timed_out.is_empty() {
+                print_timed_out(
+                    runtime_output.timed_out.as_slice(),
+                    runtime_output.failed_tests.as_slice(),
+                );
+            }
+            self.record_outcome(runtime_output.status)?;
+            self.record_test_results(
+                runtime_output.test_results.as_slice(),
+                runtime_output.failed_tests.as_slice(),
+            )?;
+            let names = self.test_names();
+            let mut flaky_names: Vec<String> = flaky
+                .iter()
+                .filter_map(|test| resolve_reported(&names, test).map(|(_, name)| name.clone()))
+                .collect();
+            // Parameters of the same test are flaky in the same run
+            flaky_names.sort();
+            flaky_names.dedup();
+            self.cache_manager
+                .record_flaky_tests(flaky_names.as_slice())?;
             // We don't want to update the cache if we are running failed tests only
             let mut updated = false;
             if !self.config.run_failed {
@@ -298,12 +685,125 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clo
                     .add_entry(self.tests.to_json()?.as_str())?;
             }
 
-            Ok(())
+            Ok(runtime_output.status)
         } else {
-            Ok(())
+            Ok(RunStatus::Passed)
         }
     }
 
+    fn list(&mut self, options: &ListOptions) -> Result<(), FztError> {
+        self.load_tests()?;
+        let items = list_items(
+            &self.tests,
+            &TestProvider::new(&self.tests, self.config.ignored_paths.as_slice()),
+            &options.granularity,
+        );
+        print_items(items.as_slice(), &options.format)
+    }
+
+    fn suite(&mut self, command: &SuiteCommand) -> Result<(), FztError> {
+        match command {
+            SuiteCommand::List => print_suites(&self.cache_manager.suites()?),
+            SuiteCommand::Edit(name) => {
+                let suites = self.cache_manager.suites()?;
+                let suite = suites
+                    .get(name)
+                    .ok_or_else(|| unknown_suite_error(name, &suites))?;
+                self.cache_manager
+                    .save_suite(name, &edit_suite(name, suite)?)?;
+            }
+            SuiteCommand::Delete(name) => {
+                if !self.cache_manager.delete_suite(name)? {
+                    return Err(unknown_suite_error(name, &self.cache_manager.suites()?));
+                }
+                println!("Deleted suite `{}`", name);
+            }
+        }
+        Ok(())
+    }
+
+    fn flaky(&mut self, options: &FlakyOptions) -> Result<(), FztError> {
+        print_flaky_report(&self.cache_manager.test_records()?, options);
+        Ok(())
+    }
+
+    fn portable(&mut self, command: &PortableCommand) -> Result<(), FztError> {
+        match command {
+            PortableCommand::Export(path) => {
+                let mut portable = Portable::default();
+                for granularity in HISTORY_GRANULARITIES {
+                    let selections: Vec<Vec<String>> = self
+                        .cache_manager
+                        .history(&granularity)?
+                        .into_iter()
+                        .filter(|selection| !selection.is_empty())
+                        .collect();
+                    if !selections.is_empty() {
+                        portable.history.insert(granularity, selections);
+                    }
+                }
+                portable.suites = self.cache_manager.suites()?;
+                let content = portable.to_json()?;
+                match path {
+                    Some(path) => {
+                        std::fs::write(path, content)?;
+                        eprintln!(
+                            "Exported {} history entries and {} suites to {}",
+                            portable
+                                .history
+                                .values()
+                                .map(|entries| entries.len())
+                                .sum::<usize>(),
+                            portable.suites.len(),
+                            path.display()
+                        );
+                    }
+                    None => println!("{}", content),
+                }
+            }
+            PortableCommand::Import(path) => {
+                let portable = Portable::from_json(std::fs::read_to_string(path)?.as_str())?;
+                self.load_tests()?;
+                let inventory = Inventory::new(&TestProvider::new(
+                    &self.tests,
+                    self.config.ignored_paths.as_slice(),
+                ));
+                let mut imported_history = 0;
+                for (granularity, selections) in portable.history.iter() {
+                    let selections: Vec<Vec<String>> = selections
+                        .iter()
+                        .map(|selection| inventory.retain_history(granularity, selection))
+                        .collect();
+                    imported_history += self
+                        .cache_manager
+                        .import_history(selections.as_slice(), granularity)?;
+                }
+                let mut imported_suites = 0;
+                for (name, suite) in portable.suites.iter() {
+                    if let Err(error) = validate_suite_name(name) {
+                        println!("[SKIPPED] {}", error);
+                        continue;
+                    }
+                    let suite = inventory.retain_suite(suite);
+                    if suite.is_empty() {
+                        println!(
+                            "[SKIPPED] suite `{}` has no item left in application.",
+                            name
+                        );
+                        continue;
+                    }
+                    self.cache_manager.save_suite(name, &suite)?;
+                    imported_suites += 1;
+                }
+                println!(
+                    "Imported {} history entries and {} suites",
+                    imported_history, imported_suites
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn meta_data(&self) -> Result<String, FztError> {
         let meta_data = MetaData {
             runner_name: self.runner_name.clone(),
@@ -318,3 +818,36 @@ impl<SE: SearchEngine, RT: Runtime, T: Tests + DeserializeOwned, CM: Cache + Clo
         &self.root_path
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn resolve_reported_tests() {
+        let names = HashMap::from([
+            (
+                "tests/test_a.py::test_x".to_string(),
+                "tests/test_a.py::test_x".to_string(),
+            ),
+            (
+                "tests::alpha".to_string(),
+                "src/lib.rs::tests::alpha".to_string(),
+            ),
+        ]);
+        let resolved = |reported: &str| {
+            resolve_reported(&names, reported)
+                .map(|(argument, name)| (argument.as_str(), name.as_str()))
+        };
+        assert_eq!(
+            resolved("tests::alpha"),
+            Some(("tests::alpha", "src/lib.rs::tests::alpha"))
+        );
+        assert_eq!(
+            resolved("tests/test_a.py::test_x[1-2]"),
+            Some(("tests/test_a.py::test_x", "tests/test_a.py::test_x"))
+        );
+        assert_eq!(resolved("tests/test_a.py::test_y[1]"), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     cache::{Cache, manager::HistoryGranularity},
     errors::FztError,
+    runtime::RunStatus,
     search_engine::SearchEngine,
 };
 
@@ -14,16 +15,18 @@ impl<CM: Cache> HistoryProvider<CM> {
         Self { cache_manager }
     }
 
+    /// Select from the history, the selection is moved up if `update` is set
     pub fn history<SE: SearchEngine>(
         &self,
         granularity: &HistoryGranularity,
         search_engine: &SE,
         query: &Option<String>,
+        update: bool,
     ) -> Result<Vec<String>, FztError> {
         {
             let history = self.cache_manager.history(granularity)?;
             let selection = search_engine.get_from_history(history.as_slice(), query)?;
-            if selection.len() > 0 {
+            if update && selection.len() > 0 {
                 self.cache_manager
                     .update_history(selection.iter().as_ref(), granularity)?;
             }
@@ -44,4 +47,92 @@ impl<CM: Cache> HistoryProvider<CM> {
             .update_history(update.iter().as_ref(), granularity)?;
         Ok(())
     }
+
+    pub fn record_outcome(
+        &self,
+        granularity: &HistoryGranularity,
+        outcome: RunStatus,
+    ) -> Result<(), FztError> {
+        self.cache_manager.record_outcome(granularity, outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+    use crate::{
+        cache::{manager::LocalCacheManager, types::TestRecord},
+        runner::config::Preview,
+        search_engine::{Append, TestSelection},
+        tests::TestLines,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Selects the last history entry
+    struct OldestEntry;
+
+    impl SearchEngine for OldestEntry {
+        fn get_tests_to_run(
+            &self,
+            _all_test: &[&str],
+            _preview: &Option<Preview>,
+            _query: &Option<String>,
+            _records: &BTreeMap<String, TestRecord>,
+            _lines: &HashMap<String, TestLines>,
+        ) -> Result<TestSelection, FztError> {
+            unimplemented!()
+        }
+
+        fn get_from_history(
+            &self,
+            history: &[Vec<String>],
+            _query: &Option<String>,
+        ) -> Result<Vec<String>, FztError> {
+            Ok(history.last().cloned().unwrap_or_default())
+        }
+
+        fn name(&self) -> String {
+            String::from("oldest")
+        }
+
+        fn appened(&self, _selected_items: &str) -> Result<Append, FztError> {
+            unimplemented!()
+        }
+
+        fn select(&self, _selected_items: &[&str]) -> Result<String, FztError> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn update_history_only_if_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_manager = LocalCacheManager::new_in(dir.path(), "project");
+        for test in ["test_a", "test_b"] {
+            cache_manager
+                .update_history(&[test.to_string()], &HistoryGranularity::Test)
+                .unwrap();
+        }
+        let provider = HistoryProvider::new(cache_manager);
+
+        // Dry runs select without moving the entry up
+        let selection = provider
+            .history(&HistoryGranularity::Test, &OldestEntry, &None, false)
+            .unwrap();
+        assert_eq!(selection, vec!["test_a".to_string()]);
+        assert_eq!(
+            provider.last(&HistoryGranularity::Test).unwrap(),
+            vec!["test_b".to_string()]
+        );
+
+        provider
+            .history(&HistoryGranularity::Test, &OldestEntry, &None, true)
+            .unwrap();
+        assert_eq!(
+            provider.last(&HistoryGranularity::Test).unwrap(),
+            vec!["test_a".to_string()]
+        );
+    }
 }
@@ -1,7 +1,5 @@
-use std::env;
-
 use crate::{
-    cache::Cache,
+    cache::{Cache, helper::project_root},
     errors::FztError,
     runner::{RunnerName, general_runner::GeneralCacheRunner},
     runtime::{Debugger, java::gradle::GradleRuntime},
@@ -24,14 +22,14 @@ pub fn get_java_runner<SE: SearchEngine + 'static, CM: Cache + Clone + 'static>(
             ));
         }
     }
-    let path = env::current_dir()?;
+    let path = project_root()?;
     let path_str = path.to_string_lossy();
     match (
         test_framework.to_lowercase().as_str(),
         runtime.to_lowercase().as_str(),
     ) {
         ("junit5", "gradle") => Ok(Box::new(GeneralCacheRunner::new(
-            GradleRuntime::default(),
+            GradleRuntime::new(config.runtime_config.clone()),
             config,
             JavaTests::new_empty(path_str.to_string()),
             RunnerName::JavaJunit5Runner,
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{
+    errors::FztError,
+    tests::{
+        Test, Tests,
+        test_provider::{SelectGranularity, TestProvider},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListFormat {
+    Plain,
+    Json,
+    Ndjson,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    pub granularity: SelectGranularity,
+    pub format: ListFormat,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ListItem {
+    pub item: String,
+    pub path: String,
+    pub runtime_arguments: Vec<String>,
+    pub failed: bool,
+    pub covered: bool,
+}
+
+impl ListItem {
+    fn plain(&self) -> String {
+        let mut state = vec![];
+        if self.failed {
+            state.push("failed");
+        }
+        if self.covered {
+            state.push("covered");
+        }
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.item,
+            self.path,
+            self.runtime_arguments.join(" "),
+            if state.is_empty() {
+                "-".to_string()
+            } else {
+                state.join(",")
+            }
+        )
+    }
+}
+
+fn runtime_arguments<T: Test>(tests: Vec<T>) -> HashSet<String> {
+    tests.iter().map(|test| test.runtime_argument()).collect()
+}
+
+pub fn list_items<T: Tests>(
+    tests: &T,
+    test_provider: &TestProvider,
+    granularity: &SelectGranularity,
+) -> Vec<ListItem> {
+    let file_paths: HashMap<String, String> = tests
+        .tests()
+        .iter()
+        .map(|test| (test.runtime_argument(), test.file_path()))
+        .collect();
+    let failed = runtime_arguments(tests.tests_failed());
+    let covered = runtime_arguments(tests.get_covered_tests());
+
+    let mut items: Vec<ListItem> = test_provider
+        .select_option(granularity)
+        .into_iter()
+        .map(|item| {
+            let runtime_arguments =
+                test_provider.runtime_arguments(granularity, &[item.to_string()]);
+            let path = match granularity {
+                SelectGranularity::File | SelectGranularity::Directory => item.to_string(),
+                SelectGranularity::Test | SelectGranularity::RunTime => runtime_arguments
+                    .first()
+                    .and_then(|argument| file_paths.get(argument))
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            ListItem {
+                item: item.to_string(),
+                path,
+                failed: runtime_arguments
+                    .iter()
+                    .any(|argument| failed.contains(argument)),
+                covered: runtime_arguments
+                    .iter()
+                    .any(|argument| covered.contains(argument)),
+                runtime_arguments,
+            }
+        })
+        .collect();
+    items.sort_by(|a, b| a.item.cmp(&b.item));
+    items
+}
+
+pub fn print_items(items: &[ListItem], format: &ListFormat) -> Result<(), FztError> {
+    match format {
+        ListFormat::Plain => items.iter().for_each(|item| println!("{}", item.plain())),
+        ListFormat::Json => println!("{}", serde_json::to_string_pretty(items)?),
+        ListFormat::Ndjson => {
+            for item in items {
+                println!("{}", serde_json::to_string(item)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::tests::python::rust_python::tests::RustPythonTests;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn python_tests() -> RustPythonTests {
+        let mut tests = RustPythonTests::new(
+            "root".to_string(),
+            0,
+            0,
+            HashMap::from([
+                (
+                    "tests/test_a.py".to_string(),
+                    HashSet::from(["test_one".to_string(), "test_two".to_string()]),
+                ),
+                (
+                    "tests/sub/test_b.py".to_string(),
+                    HashSet::from(["test_three".to_string()]),
+                ),
+            ]),
+        );
+        tests.failed_tests = HashMap::from([(
+            "tests/test_a.py".to_string(),
+            HashSet::from(["test_two".to_string()]),
+        )]);
+        tests
+    }
+
+    #[test]
+    fn list_tests() {
+        let tests = python_tests();
+        let test_provider = TestProvider::new(&tests, &[]);
+        let items = list_items(&tests, &test_provider, &SelectGranularity::Test);
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[2],
+            ListItem {
+                item: "tests/test_a.py::test_two".to_string(),
+                path: "tests/test_a.py".to_string(),
+                runtime_arguments: vec!["tests/test_a.py::test_two".to_string()],
+                failed: true,
+                covered: false,
+            }
+        );
+        assert_eq!(
+            items[2].plain(),
+            "tests/test_a.py::test_two\ttests/test_a.py\ttests/test_a.py::test_two\tfailed"
+        );
+    }
+
+    #[test]
+    fn list_directories() {
+        let tests = python_tests();
+        let test_provider = TestProvider::new(&tests, &[]);
+        let items = list_items(&tests, &test_provider, &SelectGranularity::Directory);
+        let directories: Vec<(&str, usize, bool)> = items
+            .iter()
+            .map(|item| {
+                (
+                    item.path.as_str(),
+                    item.runtime_arguments.len(),
+                    item.failed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            directories,
+            vec![("tests", 2, true), ("tests/sub", 1, false)]
+        );
+    }
+}
@@ -1,18 +1,33 @@
 use serde::{Deserialize, Serialize};
 use std::sync::mpsc::Receiver;
 
-use crate::errors::FztError;
+use crate::{errors::FztError, runtime::RunStatus};
+
+use flaky::FlakyOptions;
+use list::ListOptions;
+use portable::PortableCommand;
+use suite::SuiteCommand;
 
 pub mod config;
+pub mod filter;
+pub mod flaky;
 pub mod general_runner;
 pub mod java;
+pub mod list;
+pub mod portable;
 pub mod python;
+pub mod repeat;
 pub mod rust;
+pub mod suite;
 
 mod history_provider;
 
 pub trait Runner {
-    fn run(&mut self, receiver: Option<Receiver<String>>) -> Result<(), FztError>;
+    fn run(&mut self, receiver: Option<Receiver<String>>) -> Result<RunStatus, FztError>;
+    fn list(&mut self, options: &ListOptions) -> Result<(), FztError>;
+    fn suite(&mut self, command: &SuiteCommand) -> Result<(), FztError>;
+    fn portable(&mut self, command: &PortableCommand) -> Result<(), FztError>;
+    fn flaky(&mut self, options: &FlakyOptions) -> Result<(), FztError>;
     fn meta_data(&self) -> Result<String, FztError>;
     fn root_path(&self) -> &str;
 }
@@ -0,0 +1,230 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::{
+        envelope::{Envelope, PORTABLE_VERSION, migrate_portable, open},
+        manager::HistoryGranularity,
+        types::Suite,
+    },
+    errors::FztError,
+    tests::test_provider::{SelectGranularity, TestProvider},
+};
+
+pub const HISTORY_GRANULARITIES: [HistoryGranularity; 5] = [
+    HistoryGranularity::Test,
+    HistoryGranularity::File,
+    HistoryGranularity::Directory,
+    HistoryGranularity::RunTime,
+    HistoryGranularity::Append,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortableCommand {
+    // Print to stdout if no file is given
+    Export(Option<PathBuf>),
+    Import(PathBuf),
+}
+
+/// History and suites of a project that can be shared in the repository.
+/// Items are relative to the project root, so the file does not depend on where the project lives.
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct Portable {
+    #[serde(default)]
+    pub history: BTreeMap<HistoryGranularity, Vec<Vec<String>>>,
+    #[serde(default)]
+    pub suites: BTreeMap<String, Suite>,
+}
+
+impl Portable {
+    pub fn to_json(&self) -> Result<String, FztError> {
+        Ok(serde_json::to_string_pretty(&Envelope {
+            version: PORTABLE_VERSION,
+            data: self,
+        })?)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, FztError> {
+        let (version, data) = open(content)?;
+        migrate_portable(version, data)
+    }
+}
+
+fn select_granularity(granularity: &HistoryGranularity) -> Option<SelectGranularity> {
+    match granularity {
+        HistoryGranularity::Test => Some(SelectGranularity::Test),
+        HistoryGranularity::File => Some(SelectGranularity::File),
+        HistoryGranularity::Directory => Some(SelectGranularity::Directory),
+        HistoryGranularity::RunTime => Some(SelectGranularity::RunTime),
+        HistoryGranularity::Append => None,
+    }
+}
+
+/// Items of the current test inventory, imported entries are checked against it
+pub struct Inventory {
+    items: HashMap<SelectGranularity, HashSet<String>>,
+}
+
+impl Inventory {
+    pub fn new(test_provider: &TestProvider) -> Self {
+        let items = [
+            SelectGranularity::Test,
+            SelectGranularity::File,
+            SelectGranularity::Directory,
+            SelectGranularity::RunTime,
+        ]
+        .into_iter()
+        .map(|select| {
+            (
+                select,
+                test_provider
+                    .select_option(&select)
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            )
+        })
+        .collect();
+        Self { items }
+    }
+
+    fn known(&self, select: &SelectGranularity, item: &str) -> bool {
+        let known = self
+            .items
+            .get(select)
+            .is_some_and(|items| items.contains(item));
+        if !known {
+            println!(
+                "[SKIPPED] {item} {} could not be found in application (was it removed or renamed?).",
+                select.to_string().to_lowercase()
+            );
+        }
+        known
+    }
+
+    fn known_append_item(&self, item: &str) -> bool {
+        // Append history items are stored as `<SelectGranularity> <item>`
+        match item.split_once(' ') {
+            Some((select, selected_item)) => SelectGranularity::from_str(select)
+                .is_ok_and(|select| self.known(&select, selected_item)),
+            None => {
+                println!("[SKIPPED] {item} is not a valid append history item.");
+                false
+            }
+        }
+    }
+
+    /// Keep only the items of the selection that exist in the inventory
+    pub fn retain_history(
+        &self,
+        granularity: &HistoryGranularity,
+        selection: &[String],
+    ) -> Vec<String> {
+        selection
+            .iter()
+            .filter(|item| match select_granularity(granularity) {
+                Some(select) => self.known(&select, item),
+                None => self.known_append_item(item),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Keep only the items of the suite that exist in the inventory
+    pub fn retain_suite(&self, suite: &Suite) -> Suite {
+        Suite::new(
+            suite
+                .selection
+                .iter()
+                .map(|(select, selected_items)| {
+                    (
+                        *select,
+                        selected_items
+                            .iter()
+                            .filter(|item| self.known(select, item))
+                            .cloned()
+                            .collect::<Vec<String>>(),
+                    )
+                })
+                .filter(|(_, selected_items)| !selected_items.is_empty())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn validate_imported_items() {
+        let inventory = Inventory {
+            items: HashMap::from([
+                (
+                    SelectGranularity::Test,
+                    HashSet::from(["tests/test_pay.py::test_refund".to_string()]),
+                ),
+                (
+                    SelectGranularity::File,
+                    HashSet::from(["tests/test_pay.py".to_string()]),
+                ),
+            ]),
+        };
+        assert_eq!(
+            inventory.retain_history(
+                &HistoryGranularity::Test,
+                &[
+                    "tests/test_pay.py::test_refund".to_string(),
+                    "tests/test_pay.py::test_removed".to_string()
+                ]
+            ),
+            vec!["tests/test_pay.py::test_refund".to_string()]
+        );
+        assert_eq!(
+            inventory.retain_history(
+                &HistoryGranularity::Append,
+                &[
+                    "File tests/test_pay.py".to_string(),
+                    "Directory tests".to_string(),
+                    "tests/test_pay.py".to_string()
+                ]
+            ),
+            vec!["File tests/test_pay.py".to_string()]
+        );
+        let suite = Suite::new(BTreeMap::from([
+            (
+                SelectGranularity::Test,
+                vec!["tests/test_pay.py::test_refund".to_string()],
+            ),
+            (SelectGranularity::Directory, vec!["tests".to_string()]),
+        ]));
+        assert_eq!(
+            inventory.retain_suite(&suite),
+            Suite::new(BTreeMap::from([(
+                SelectGranularity::Test,
+                vec!["tests/test_pay.py::test_refund".to_string()],
+            )]))
+        );
+    }
+
+    #[test]
+    fn portable_round_trip() {
+        let portable = Portable {
+            history: BTreeMap::from([(
+                HistoryGranularity::File,
+                vec![vec!["tests/test_pay.py".to_string()]],
+            )]),
+            suites: BTreeMap::new(),
+        };
+        let json = portable.to_json().unwrap();
+        assert!(json.contains("\"file\""));
+        assert_eq!(Portable::from_json(json.as_str()).unwrap(), portable);
+        assert!(Portable::from_json("[[\"test_a\"]]").is_err());
+    }
+}
@@ -1,7 +1,5 @@
-use std::env;
-
 use crate::{
-    cache::Cache,
+    cache::{Cache, helper::project_root},
     errors::FztError,
     runner::{RunnerName, general_runner::GeneralCacheRunner},
     runtime::{Debugger, PythonDebugger, python::pytest::PytestRuntime},
@@ -49,14 +47,14 @@ pub fn get_python_runner<SE: SearchEngine + 'static, CM: Cache + Clone + 'static
         }
     }
 
-    let path = env::current_dir()?;
+    let path = project_root()?;
     let path_str = path.to_string_lossy();
     match (
         parser.to_lowercase().as_str(),
         runtime.to_lowercase().as_str(),
     ) {
         ("rustpython", "pytest") => Ok(Box::new(GeneralCacheRunner::new(
-            PytestRuntime::default(),
+            PytestRuntime::new(config.runtime_config.clone()),
             config,
             RustPythonTests::new_empty(path_str.to_string()),
             RunnerName::RustPythonRunner,
@@ -64,7 +62,7 @@ pub fn get_python_runner<SE: SearchEngine + 'static, CM: Cache + Clone + 'static
             path_str.to_string(),
         ))),
         ("pytest", "pytest") => Ok(Box::new(GeneralCacheRunner::new(
-            PytestRuntime::default(),
+            PytestRuntime::new(config.runtime_config.clone()),
             config,
             PytestTests::new_empty(path_str.to_string()),
             RunnerName::PytestRunner,
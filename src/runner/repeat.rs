@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, io::Write, path::PathBuf};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tempfile::Builder;
+
+use crate::{
+    errors::FztError,
+    runtime::{TestResult, TestStatus},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepeatOptions {
+    // Number of repetitions, unlimited if not set
+    pub count: Option<usize>,
+    pub until_fail: bool,
+}
+
+/// Passed and failed runs per test
+#[derive(Debug, Default, PartialEq)]
+pub struct Tally {
+    runs: BTreeMap<String, (usize, usize)>,
+}
+
+impl Tally {
+    pub fn add(&mut self, results: &[TestResult]) {
+        results.iter().for_each(|result| {
+            let runs = self.runs.entry(result.name.clone()).or_default();
+            match result.status {
+                TestStatus::Passed => runs.0 += 1,
+                TestStatus::Failed => runs.1 += 1,
+                TestStatus::Skipped => {}
+            }
+        });
+    }
+
+    pub fn format(&self) -> String {
+        self.runs
+            .iter()
+            .map(|(test, (passed, failed))| {
+                format!("{:>5} passed {:>5} failed  {}\n", passed, failed, test)
+            })
+            .collect()
+    }
+
+    pub fn print(&self, repetitions: usize) {
+        println!(
+            "\n{}",
+            format!("Results of {} repetitions:", repetitions).bold()
+        );
+        print!("{}", self.format());
+    }
+}
+
+/// The tests `repetitions` times in selection order, the copies run at the same time
+pub fn repeated_tests(tests: &[String], repetitions: usize) -> Vec<String> {
+    (0..repetitions)
+        .flat_map(|_| tests.iter().cloned())
+        .collect()
+}
+
+/// Keep the output of a failed repetition or test, returns the path of the file
+pub fn save_output(label: &str, output: &str) -> Result<PathBuf, FztError> {
+    // Test names can contain path separators and `::`
+    let label: String = label
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || "-_.".contains(ch) {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut file = Builder::new()
+        .prefix(format!("fzt-repeat-{}-", label).as_str())
+        .suffix(".log")
+        .tempfile()?;
+    file.write_all(output.as_bytes())?;
+    file.flush()?;
+    Ok(file.into_temp_path().keep().map_err(|error| error.error)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tally_results() {
+        let mut tally = Tally::default();
+        tally.add(&[
+            TestResult::new("test_a", TestStatus::Passed, Some(0.1)),
+            TestResult::new("test_b", TestStatus::Failed, Some(0.1)),
+            TestResult::new("test_a", TestStatus::Passed, Some(0.1)),
+        ]);
+        tally.add(&[
+            TestResult::new("test_a", TestStatus::Failed, None),
+            TestResult::new("test_b", TestStatus::Skipped, None),
+        ]);
+        assert_eq!(
+            tally.format(),
+            "    2 passed     1 failed  test_a\n    0 passed     1 failed  test_b\n"
+        );
+        assert_eq!(
+            repeated_tests(&["test_a".to_string(), "test_b".to_string()], 2),
+            vec!["test_a", "test_b", "test_a", "test_b"]
+        );
+    }
+
+    #[test]
+    fn save_failed_output() {
+        let path = save_output("tests/test_pay.py::test_refund[1]", "assert 1 == 2").unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap().to_string();
+        assert!(name.starts_with("fzt-repeat-tests_test_pay.py__test_refund_1_-"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "assert 1 == 2");
+        std::fs::remove_file(path).unwrap();
+    }
+}
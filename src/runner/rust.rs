@@ -1,7 +1,5 @@
-use std::env;
-
 use crate::{
-    cache::Cache,
+    cache::{Cache, helper::project_root},
     errors::FztError,
     runner::{RunnerName, general_runner::GeneralCacheRunner},
     runtime::{
@@ -26,11 +24,11 @@ pub fn get_rust_runner<SE: SearchEngine + 'static, CM: Cache + Clone + 'static>(
             ));
         }
     }
-    let path = env::current_dir()?;
+    let path = project_root()?;
     let path_str = path.to_string_lossy();
     match runtime.to_lowercase().as_str() {
         "cargo" => Ok(Box::new(GeneralCacheRunner::new(
-            CargoRuntime::default(),
+            CargoRuntime::new(config.runtime_config.clone()),
             config,
             RustTests::new_empty(path_str.to_string()),
             RunnerName::RustCargoRunner,
@@ -38,7 +36,7 @@ pub fn get_rust_runner<SE: SearchEngine + 'static, CM: Cache + Clone + 'static>(
             path_str.to_string(),
         ))),
         "nextest" => Ok(Box::new(GeneralCacheRunner::new(
-            NextestRuntime::default(),
+            NextestRuntime::new(config.runtime_config.clone()),
             config,
             RustTests::new_empty(path_str.to_string()),
             RunnerName::RustNextestRunner,
@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, io::Write};
+
+use colored::Colorize;
+use tempfile::Builder;
+
+use crate::{
+    cache::types::Suite, errors::FztError, tests::test_provider::SelectGranularity,
+    utils::file::open_in_editor,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuiteCommand {
+    List,
+    Edit(String),
+    Delete(String),
+}
+
+pub fn format_suites(suites: &BTreeMap<String, Suite>) -> String {
+    let mut output = String::new();
+    suites.iter().for_each(|(name, suite)| {
+        output.push_str(&format!("{} ({} items)\n", name.bold(), suite.len()));
+        suite.selection.iter().for_each(|(select, selected_items)| {
+            selected_items.iter().for_each(|item| {
+                output.push_str(&format!("  {:<10} {}\n", select.to_string(), item));
+            });
+        });
+    });
+    output
+}
+
+pub fn print_suites(suites: &BTreeMap<String, Suite>) {
+    if suites.is_empty() {
+        println!("No saved suites. Save a selection with `fzt --save <NAME>`.");
+    } else {
+        print!("{}", format_suites(suites));
+    }
+}
+
+/// Suite names are used in file names, e.g. when editing the suite
+pub fn validate_suite_name(name: &str) -> Result<(), FztError> {
+    if name.trim().is_empty() || name.contains(['/', '\\']) {
+        return Err(FztError::InvalidArgument(format!(
+            "Invalid suite name `{}`. Suite names can not be empty or contain `/` or `\\`.",
+            name
+        )));
+    }
+    Ok(())
+}
+
+pub fn unknown_suite_error(name: &str, suites: &BTreeMap<String, Suite>) -> FztError {
+    FztError::InvalidArgument(format!(
+        "No suite named `{}`. Saved suites: {}",
+        name,
+        suites.keys().cloned().collect::<Vec<String>>().join(", ")
+    ))
+}
+
+/// Open the selection of the suite as JSON in the editor and parse the result.
+/// An empty selection is rejected, the suite is kept unchanged.
+pub fn edit_suite(name: &str, suite: &Suite) -> Result<Suite, FztError> {
+    validate_suite_name(name)?;
+    let mut file = Builder::new()
+        .prefix(format!("fzt-suite-{}-", name).as_str())
+        .suffix(".json")
+        .tempfile()?;
+    file.write_all(serde_json::to_string_pretty(&suite.selection)?.as_bytes())?;
+    file.flush()?;
+    open_in_editor(file.path())?;
+    let content = std::fs::read_to_string(file.path())?;
+    let selection: BTreeMap<SelectGranularity, Vec<String>> = serde_json::from_str(&content)
+        .map_err(|error| {
+            FztError::InvalidArgument(format!(
+                "Suite `{}` was not changed, the edited selection is invalid: {}",
+                name, error
+            ))
+        })?;
+    let edited = Suite::new(selection);
+    if edited.is_empty() {
+        return Err(FztError::InvalidArgument(format!(
+            "Suite `{}` was not changed, the edited selection is empty. \
+            Use `fzt suite delete {}` to remove it.",
+            name, name
+        )));
+    }
+    Ok(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn suite_selection_format() {
+        colored::control::set_override(false);
+        let suite = Suite::new(BTreeMap::from([
+            (SelectGranularity::Test, vec!["test_a".to_string()]),
+            (SelectGranularity::File, vec!["src/lib.rs".to_string()]),
+        ]));
+        let json = serde_json::to_string(&suite.selection).unwrap();
+        assert_eq!(json, "{\"Test\":[\"test_a\"],\"File\":[\"src/lib.rs\"]}");
+        assert_eq!(
+            serde_json::from_str::<BTreeMap<SelectGranularity, Vec<String>>>(&json).unwrap(),
+            suite.selection
+        );
+        assert_eq!(
+            format_suites(&BTreeMap::from([("smoke".to_string(), suite)])),
+            "smoke (2 items)\n  Test       test_a\n  File       src/lib.rs\n"
+        );
+    }
+
+    #[test]
+    fn suite_names() {
+        assert!(validate_suite_name("smoke-tests").is_ok());
+        assert!(validate_suite_name("api/smoke").is_err());
+        assert!(validate_suite_name("..\\smoke").is_err());
+        assert!(validate_suite_name(" ").is_err());
+    }
+}
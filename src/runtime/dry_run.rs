@@ -0,0 +1,130 @@
+use std::{path::Path, process::Command};
+
+use serde::{Deserialize, Serialize};
+
+/// Output of `--dry-run`, commands are printed instead of executed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum DryRun {
+    Plain,
+    Script,
+}
+
+/// Quote the argument for the shell if needed
+pub fn quote(arg: &str) -> String {
+    let is_safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "_-./:=@%+,".contains(ch));
+    if is_safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Render the command with its environment variables, so it can be copied into a shell
+pub fn render_command(command: &Command) -> String {
+    let envs = command.get_envs().filter_map(|(key, value)| {
+        value.map(|value| {
+            format!(
+                "{}={}",
+                key.to_string_lossy(),
+                quote(value.to_string_lossy().as_ref())
+            )
+        })
+    });
+    let program = std::iter::once(quote(command.get_program().to_string_lossy().as_ref()));
+    let args = command
+        .get_args()
+        .map(|arg| quote(arg.to_string_lossy().as_ref()));
+    envs.chain(program)
+        .chain(args)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Each worker runs its commands one after the other, workers run in parallel
+fn render(dry_run: &DryRun, workers: &[Vec<String>], root: &Path) -> String {
+    let mut output = String::new();
+    match dry_run {
+        DryRun::Plain => {
+            for (index, commands) in workers.iter().enumerate() {
+                if workers.len() > 1 {
+                    output.push_str(format!("# worker {}\n", index + 1).as_str());
+                }
+                commands.iter().for_each(|command| {
+                    output.push_str(command);
+                    output.push('\n');
+                });
+            }
+        }
+        DryRun::Script => {
+            output.push_str("#!/usr/bin/env bash\n");
+            output.push_str(format!("cd {}\n", quote(root.to_string_lossy().as_ref())).as_str());
+            if workers.len() > 1 {
+                for commands in workers {
+                    output.push_str("(\n");
+                    commands.iter().for_each(|command| {
+                        output.push_str(format!("    {command}\n").as_str());
+                    });
+                    output.push_str(") &\n");
+                }
+                output.push_str("wait\n");
+            } else {
+                workers.iter().flatten().for_each(|command| {
+                    output.push_str(command);
+                    output.push('\n');
+                });
+            }
+        }
+    }
+    output
+}
+
+pub fn print_commands(dry_run: &DryRun, workers: &[Vec<String>]) {
+    let root = std::env::current_dir().unwrap_or_default();
+    print!("{}", render(dry_run, workers, root.as_path()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_command_with_envs() {
+        let mut command = Command::new("cargo");
+        command.args(["test", "tests::it works", "--", "--exact"]);
+        command.env("RUST_LOG", "debug info");
+        command.env("A", "1");
+        assert_eq!(
+            render_command(&command),
+            "A=1 RUST_LOG='debug info' cargo test 'tests::it works' -- --exact"
+        );
+        assert_eq!(quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn render_parallel_script() {
+        let workers = vec![
+            vec!["cargo test a".to_string(), "cargo test b".to_string()],
+            vec!["cargo test c".to_string()],
+        ];
+        assert_eq!(
+            render(
+                &DryRun::Script,
+                workers.as_slice(),
+                Path::new("/tmp/project")
+            ),
+            "#!/usr/bin/env bash\ncd /tmp/project\n(\n    cargo test a\n    cargo test b\n) &\n(\n    cargo test c\n) &\nwait\n"
+        );
+        assert_eq!(
+            render(
+                &DryRun::Plain,
+                workers.as_slice(),
+                Path::new("/tmp/project")
+            ),
+            "# worker 1\ncargo test a\ncargo test b\n# worker 2\ncargo test c\n"
+        );
+    }
+}
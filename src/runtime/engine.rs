@@ -1,14 +1,19 @@
 use crate::FztError;
-use crate::runtime::process::run_and_capture_print;
+use crate::runtime::process::{StringReceiver, run_and_capture_print, run_and_capture_print_pty};
 use crate::runtime::utils::partition_tests;
 use crossbeam_channel::{Receiver as CrossbeamReceiver, unbounded};
 use std::sync::mpsc::Receiver as StdReceiver;
+use std::time::{Duration, Instant};
 use std::{collections::HashMap, process::Command};
 
+use super::dry_run::{DryRun, print_commands, render_command};
 use super::process::CaptureOutput;
-use super::{FailedTest, OutputFormatter, RuntimeOutput};
+use super::{
+    FailedTest, OutputFormatter, RunStatus, RuntimeConfig, RuntimeOutput, TestResult, TestStatus,
+};
 
 const NUMBER_THREADS: usize = 16;
+const TEST_FAILURE_EXIT_CODE: i32 = 1;
 
 pub struct EngineOutput<F: OutputFormatter + Clone + Sync + Send + Default> {
     test_outputs: Vec<TestOutput<F>>,
@@ -45,6 +50,23 @@ impl<F: OutputFormatter + Clone + Sync + Send + Default> EngineOutput<F> {
             .collect()
     }
 
+    pub fn status(&self, test_failure_exit_code: i32) -> RunStatus {
+        if !self.success(test_failure_exit_code) {
+            RunStatus::RuntimeError
+        } else if !self.failed_tests().is_empty()
+            || self.test_outputs.iter().any(|test_output| {
+                test_output
+                    .output
+                    .status
+                    .is_some_and(|status| !status.success())
+            })
+        {
+            RunStatus::TestsFailed
+        } else {
+            RunStatus::Passed
+        }
+    }
+
     pub fn stopped(&self) -> bool {
         self.test_outputs
             .iter()
@@ -54,7 +76,38 @@ impl<F: OutputFormatter + Clone + Sync + Send + Default> EngineOutput<F> {
     pub fn failed_tests(&self) -> Vec<FailedTest> {
         self.test_outputs
             .iter()
-            .flat_map(|test_output| test_output.formatter.failed_tests())
+            .flat_map(|test_output| {
+                let mut failed_tests = test_output.formatter.failed_tests();
+                if let Some(timeout) = test_output.timed_out {
+                    failed_tests.push(FailedTest::timed_out(&test_output.test, timeout));
+                }
+                failed_tests
+            })
+            .collect()
+    }
+
+    pub fn test_results(&self) -> Vec<TestResult> {
+        self.test_outputs
+            .iter()
+            .flat_map(|test_output| {
+                let mut test_results = test_output.formatter.test_results();
+                if let Some(timeout) = test_output.timed_out {
+                    test_results.push(TestResult::new(
+                        &test_output.test,
+                        TestStatus::Failed,
+                        Some(timeout.as_secs_f64()),
+                    ));
+                }
+                test_results
+            })
+            .collect()
+    }
+
+    pub fn timed_out(&self) -> Vec<String> {
+        self.test_outputs
+            .iter()
+            .filter(|test_output| test_output.timed_out.is_some())
+            .map(|test_output| test_output.test.clone())
             .collect()
     }
 
@@ -103,6 +156,17 @@ impl<F: OutputFormatter + Clone + Sync + Send + Default> EngineOutput<F> {
         merged_stdout
     }
 
+    /// Output of each item that failed, was killed or stopped, by item.
+    /// Items that timed out before they started have no output and are skipped.
+    pub fn failed_stdout(&self) -> Vec<(String, String)> {
+        self.test_outputs
+            .iter()
+            .filter(|output| !output.output.status.is_some_and(|s| s.success()))
+            .filter(|output| !(output.timed_out.is_some() && output.output.stdout.is_empty()))
+            .map(|output| (output.test.clone(), output.output.stdout.clone()))
+            .collect()
+    }
+
     pub fn get_test_outputs(&self) -> &[TestOutput<F>] {
         &self.test_outputs.as_slice()
     }
@@ -113,6 +177,8 @@ pub struct TestOutput<F: OutputFormatter + Clone + Sync + Send> {
     pub output: CaptureOutput,
     pub test: String,
     pub formatter: F,
+    // Timeout that expired while the test was running
+    pub timed_out: Option<Duration>,
 }
 
 #[derive(Debug, Clone)]
@@ -130,6 +196,24 @@ pub struct Engine {
     runtime_command_args_separator: Option<String>,
     number_threads: usize,
     command_envs: HashMap<String, String>,
+    test_failure_exit_code: i32,
+    dry_run: Option<DryRun>,
+    pty: bool,
+    item_timeout: Option<Duration>,
+    batch_timeout: Option<Duration>,
+    runtime_item_timeout: bool,
+}
+
+/// Threads used for per item runs if the runtime config does not set them
+pub fn number_threads(number_threads: Option<usize>) -> usize {
+    if let Some(number_threads) = number_threads {
+        number_threads
+    } else {
+        std::env::var("FZT_NUMBER_THREADS")
+            .ok()
+            .and_then(|t| t.parse::<usize>().ok())
+            .unwrap_or(NUMBER_THREADS)
+    }
 }
 
 impl Engine {
@@ -137,20 +221,19 @@ impl Engine {
         runtime_command_args_separator: Option<String>,
         number_threads: Option<usize>,
     ) -> Self {
-        let number_threads = if let Some(number_threads) = number_threads {
-            number_threads
-        } else {
-            std::env::var("FZT_NUMBER_THREADS")
-                .ok()
-                .and_then(|t| t.parse::<usize>().ok())
-                .unwrap_or(NUMBER_THREADS)
-        };
+        let number_threads = self::number_threads(number_threads);
         Self {
             base_command_args: vec![],
             runtime_command_args: vec![],
             runtime_command_args_separator,
             number_threads,
             command_envs: HashMap::new(),
+            test_failure_exit_code: TEST_FAILURE_EXIT_CODE,
+            dry_run: None,
+            pty: false,
+            item_timeout: None,
+            batch_timeout: None,
+            runtime_item_timeout: false,
         }
     }
 
@@ -181,6 +264,77 @@ impl Engine {
         self
     }
 
+    pub fn config(&mut self, config: &RuntimeConfig) -> &mut Self {
+        self.command_envs.extend(config.envs.clone());
+        self.dry_run = config.dry_run;
+        self.item_timeout = config.timeout.map(Duration::from_secs);
+        self.batch_timeout = config.batch_timeout.map(Duration::from_secs);
+        self
+    }
+
+    /// Exit code the runtime returns if tests failed, every other non zero code is a runtime error
+    pub fn test_failure_exit_code(&mut self, code: i32) -> &mut Self {
+        self.test_failure_exit_code = code;
+        self
+    }
+
+    /// The runtime kills tests running longer than the item timeout itself,
+    /// single batch runs are then only limited by the batch timeout
+    pub fn runtime_item_timeout(&mut self) -> &mut Self {
+        self.runtime_item_timeout = true;
+        self
+    }
+
+    /// Run commands in a pseudo terminal, so runtimes color and line buffer their output.
+    /// The terminal merges stderr into stdout.
+    pub fn pty(&mut self) -> &mut Self {
+        self.pty = true;
+        self
+    }
+
+    fn capture<F: OutputFormatter, R: StringReceiver>(
+        &self,
+        command: Command,
+        formatter: &mut F,
+        receiver: Option<R>,
+        deadline: Option<Instant>,
+    ) -> Result<CaptureOutput, FztError> {
+        if self.pty {
+            run_and_capture_print_pty(command, formatter, receiver, deadline)
+        } else {
+            run_and_capture_print(command, formatter, receiver, deadline)
+        }
+    }
+
+    // The earlier of the item and the batch deadline, with the timeout that expires at it
+    fn item_deadline(&self, batch_deadline: Option<Instant>) -> Option<(Instant, Duration)> {
+        let item_deadline = self
+            .item_timeout
+            .map(|timeout| (Instant::now() + timeout, timeout));
+        [item_deadline, batch_deadline.zip(self.batch_timeout)]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(deadline, _)| *deadline)
+    }
+
+    // Items of a batch run in one process, so fzt can not kill them one by one.
+    // If the runtime does not kill them itself, the batch may run as long as the
+    // timeouts of all its items together.
+    fn single_batch_deadline(&self, items: usize) -> Option<(Instant, Duration)> {
+        let item_timeout = self.item_timeout.filter(|_| !self.runtime_item_timeout);
+        let item_deadline = item_timeout.map(|timeout| {
+            let timeout = timeout.saturating_mul(items.max(1) as u32);
+            (Instant::now() + timeout, timeout)
+        });
+        let batch_deadline = self
+            .batch_timeout
+            .map(|timeout| (Instant::now() + timeout, timeout));
+        [item_deadline, batch_deadline]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(deadline, _)| *deadline)
+    }
+
     fn construct_command(&self, addional_args: &[String]) -> Command {
         let mut command = Command::new(&self.base_command_args[0]);
         if self.base_command_args.len() > 1 {
@@ -203,22 +357,49 @@ impl Engine {
         command.args(addional_args);
     }
 
+    fn item_command<F: OutputFormatter + Clone + Sync + Send>(
+        &self,
+        item: &TestItem<F>,
+    ) -> Command {
+        let mut command = self.construct_command(&item.additional_base_args.as_slice());
+        command.arg(item.test_name.clone());
+        self.append_runtime_args(&mut command, &item.additional_runtime_args.as_slice());
+        item.additional_command_envs
+            .iter()
+            .for_each(|(key, value)| {
+                command.env(key, value);
+            });
+        command
+    }
+
     fn run_tests_single<F: OutputFormatter + Clone + Sync + Send>(
         &self,
         test_items: Vec<TestItem<F>>,
         receiver: CrossbeamReceiver<String>,
         verbose: bool,
+        batch_deadline: Option<Instant>,
     ) -> Result<Vec<TestOutput<F>>, FztError> {
         let mut output = vec![];
         for mut item in test_items.into_iter() {
-            let mut command = self.construct_command(&item.additional_base_args.as_slice());
-            command.arg(item.test_name.clone());
-            self.append_runtime_args(&mut command, &item.additional_runtime_args.as_slice());
-            item.additional_command_envs
-                .iter()
-                .for_each(|(key, value)| {
-                    command.env(key, value);
+            let deadline = self.item_deadline(batch_deadline);
+            // Tests that did not start before the run timed out are not started anymore
+            if let Some((_, timeout)) = deadline.filter(|(deadline, _)| Instant::now() >= *deadline)
+            {
+                output.push(TestOutput {
+                    output: CaptureOutput {
+                        stopped: false,
+                        timed_out: true,
+                        stdout: String::new(),
+                        stderr: String::new(),
+                        status: None,
+                    },
+                    test: item.test_name,
+                    formatter: item.formatter,
+                    timed_out: Some(timeout),
                 });
+                continue;
+            }
+            let command = self.item_command(&item);
             if verbose {
                 let program = command.get_program().to_str().unwrap();
                 let args: Vec<String> = command
@@ -227,12 +408,20 @@ impl Engine {
                     .collect();
                 println!("\n{} {}\n", program, args.as_slice().join(" "));
             }
-            let captured =
-                run_and_capture_print(command, &mut item.formatter, Some(receiver.clone()))?;
+            let captured = self.capture(
+                command,
+                &mut item.formatter,
+                Some(receiver.clone()),
+                deadline.map(|(deadline, _)| deadline),
+            )?;
+            let timed_out = deadline
+                .filter(|_| captured.timed_out)
+                .map(|(_, timeout)| timeout);
             output.push(TestOutput {
                 output: captured,
                 test: item.test_name,
                 formatter: item.formatter,
+                timed_out,
             });
         }
         Ok(output)
@@ -251,6 +440,10 @@ impl Engine {
             command.arg(tests);
         });
         self.append_runtime_args(&mut command, &[]);
+        if let Some(dry_run) = self.dry_run.as_ref() {
+            print_commands(dry_run, &[vec![render_command(&command)]]);
+            return Ok(RuntimeOutput::new_empty());
+        }
         if verbose {
             let program = command.get_program().to_str().unwrap();
             let args: Vec<String> = command
@@ -261,17 +454,62 @@ impl Engine {
         }
 
         if debug_mode {
-            command.status()?;
-            Ok(RuntimeOutput::new_empty())
+            let status = command.status()?;
+            let mut output = RuntimeOutput::new_empty();
+            output.status =
+                RunStatus::from_exit_status(Some(status), self.test_failure_exit_code, false);
+            Ok(output)
         } else {
-            let output = run_and_capture_print(command, formatter, receiver)?;
+            let items = tests.iter().filter(|test| !test.starts_with('-')).count();
+            let deadline = self.single_batch_deadline(items);
+            let output = self.capture(
+                command,
+                formatter,
+                receiver,
+                deadline.map(|(deadline, _)| deadline),
+            )?;
             if output.stopped {
                 Ok(RuntimeOutput::new_empty())
             } else {
+                let mut failed_tests = formatter.failed_tests();
+                let mut test_results = formatter.test_results();
+                let mut status = RunStatus::from_exit_status(
+                    output.status,
+                    self.test_failure_exit_code,
+                    !failed_tests.is_empty(),
+                );
+                // Tests the runtime did not report a result for until the timeout
+                let timed_out: Vec<String> = match deadline {
+                    Some((_, timeout)) if output.timed_out => {
+                        let timed_out: Vec<String> = tests
+                            .iter()
+                            .filter(|test| !test.starts_with('-'))
+                            .filter(|test| !test_results.iter().any(|result| &result.name == *test))
+                            .cloned()
+                            .collect();
+                        timed_out.iter().for_each(|test| {
+                            failed_tests.push(FailedTest::batch_timed_out(test, timeout));
+                            test_results.push(TestResult::new(
+                                test,
+                                TestStatus::Failed,
+                                Some(timeout.as_secs_f64()),
+                            ));
+                        });
+                        if !timed_out.is_empty() {
+                            status = status.max(RunStatus::TestsFailed);
+                        }
+                        timed_out
+                    }
+                    _ => vec![],
+                };
                 Ok(RuntimeOutput {
-                    failed_tests: formatter.failed_tests(),
+                    status,
+                    failed_tests,
+                    test_results,
                     output: Some(output.stdout),
                     coverage: HashMap::new(),
+                    timed_out,
+                    failed_output: vec![],
                 })
             }
         }
@@ -283,9 +521,28 @@ impl Engine {
         test_items: Vec<TestItem<F>>,
         verbose: bool,
     ) -> Result<EngineOutput<F>, FztError> {
-        println!("\nRunning {} tests", test_items.len());
-
         let partitions = partition_tests(test_items, self.number_threads);
+        if let Some(dry_run) = self.dry_run.as_ref() {
+            let workers: Vec<Vec<String>> = partitions
+                .iter()
+                .map(|partition| {
+                    partition
+                        .iter()
+                        .map(|item| render_command(&self.item_command(item)))
+                        .collect()
+                })
+                .collect();
+            print_commands(dry_run, workers.as_slice());
+            return Ok(EngineOutput::new(vec![]));
+        }
+
+        println!(
+            "\nRunning {} tests",
+            partitions
+                .iter()
+                .map(|partition| partition.len())
+                .sum::<usize>()
+        );
         let mut local_outputs: Vec<Result<Vec<TestOutput<F>>, FztError>> =
             (0..partitions.len()).map(|_| Ok(vec![])).collect();
 
@@ -301,10 +558,12 @@ impl Engine {
                 }
             });
         }
+        let batch_deadline = self.batch_timeout.map(|timeout| Instant::now() + timeout);
         std::thread::scope(|s| {
             for (output, partition) in local_outputs.iter_mut().zip(partitions.into_iter()) {
                 s.spawn(|| {
-                    *output = self.run_tests_single(partition, cross_rx.clone(), verbose);
+                    *output =
+                        self.run_tests_single(partition, cross_rx.clone(), verbose, batch_deadline);
                 });
             }
         });
@@ -319,3 +578,63 @@ impl Engine {
         Ok(EngineOutput::new(final_output))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::rust::cargo::formatter::CargoFormatter;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn single_batch_timeout() {
+        let mut engine = Engine::new(None, Some(1));
+        engine
+            .base_args(&["sh", "-c", "echo started; sleep 30", "sh"])
+            .config(&RuntimeConfig {
+                timeout: Some(1),
+                ..RuntimeConfig::default()
+            });
+        let start = Instant::now();
+        let output = engine
+            .execute_single_batch_sequential(
+                false,
+                None,
+                vec!["test_a".to_string()],
+                &mut CargoFormatter::new(),
+                false,
+            )
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(output.timed_out, vec!["test_a".to_string()]);
+        assert_eq!(
+            output.failed_tests[0].error_msg,
+            "batch timed out after 1s".to_string()
+        );
+        assert_eq!(output.status, RunStatus::TestsFailed);
+    }
+
+    #[test]
+    fn single_batch_deadline_of_runtime_item_timeout() {
+        let mut engine = Engine::new(None, Some(1));
+        engine.config(&RuntimeConfig {
+            timeout: Some(10),
+            ..RuntimeConfig::default()
+        });
+        assert_eq!(
+            engine.single_batch_deadline(3).map(|(_, timeout)| timeout),
+            Some(Duration::from_secs(30))
+        );
+        // Only the batch timeout is left if the runtime kills the tests itself
+        engine.runtime_item_timeout();
+        assert_eq!(engine.single_batch_deadline(3), None);
+        engine.config(&RuntimeConfig {
+            batch_timeout: Some(60),
+            ..RuntimeConfig::default()
+        });
+        assert_eq!(
+            engine.single_batch_deadline(3).map(|(_, timeout)| timeout),
+            Some(Duration::from_secs(60))
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::{
     FztError,
-    runtime::{FailedTest, OutputFormatter},
+    runtime::{FailedTest, OutputFormatter, TestResult, TestStatus},
 };
 
 #[derive(Clone, Debug, Default)]
@@ -55,10 +55,21 @@ impl OutputFormatter for GradleFormatter {
         self.failed_tests.iter().cloned().collect()
     }
 
+    // Gradle only reports failed tests
+    fn test_results(&self) -> Vec<TestResult> {
+        self.failed_tests
+            .iter()
+            .map(|failed_test| TestResult::new(&failed_test.name, TestStatus::Failed, None))
+            .collect()
+    }
+
     fn update(&mut self) -> Result<(), FztError> {
         let mut last_failed_block_line = String::new();
+        // Exception and stack trace of the failed test
+        let mut failed_block: Vec<&str> = vec![];
         self.output_lines.iter().fold(false, |in_test, line| {
             if line.ends_with("FAILED") {
+                failed_block.clear();
                 return true;
             }
             if line.is_empty() && in_test {
@@ -70,12 +81,13 @@ impl OutputFormatter for GradleFormatter {
                         .trim();
                     self.failed_tests.insert(FailedTest {
                         name: method_part.to_string(),
-                        error_msg: String::new(),
+                        error_msg: failed_block.join("\n"),
                     });
                     return false;
                 }
             }
             if in_test {
+                failed_block.push(line.strip_prefix("    ").unwrap_or(line));
                 if line.trim().starts_with("at ") {
                     last_failed_block_line = line.trim().to_string();
                 }
@@ -134,11 +146,29 @@ ParserTest > parseCache() FAILED
         let expected: HashSet<FailedTest> = HashSet::from([
             FailedTest {
                 name: "org.parser.ParserTest.boo".to_string(),
-                error_msg: String::from(""),
+                error_msg: String::from(
+                    "org.opentest4j.AssertionFailedError: expected: <true> but was: <false>
+    at app//org.junit.jupiter.api.AssertionFailureBuilder.build(AssertionFailureBuilder.java:151)
+    at app//org.junit.jupiter.api.AssertionFailureBuilder.buildAndThrow(AssertionFailureBuilder.java:132)
+    at app//org.junit.jupiter.api.AssertTrue.failNotTrue(AssertTrue.java:63)
+    at app//org.junit.jupiter.api.AssertTrue.assertTrue(AssertTrue.java:36)
+    at app//org.junit.jupiter.api.AssertTrue.assertTrue(AssertTrue.java:31)
+    at app//org.junit.jupiter.api.Assertions.assertTrue(Assertions.java:183)
+    at app//org.parser.ParserTest.boo(ParserTest.java:113)",
+                ),
             },
             FailedTest {
                 name: "org.parser.ParserTest.foo".to_string(),
-                error_msg: String::from(""),
+                error_msg: String::from(
+                    "org.opentest4j.AssertionFailedError: expected: <true> but was: <false>
+    at app//org.junit.jupiter.api.AssertionFailureBuilder.build(AssertionFailureBuilder.java:151)
+    at app//org.junit.jupiter.api.AssertionFailureBuilder.buildAndThrow(AssertionFailureBuilder.java:132)
+    at app//org.junit.jupiter.api.AssertTrue.failNotTrue(AssertTrue.java:63)
+    at app//org.junit.jupiter.api.AssertTrue.assertTrue(AssertTrue.java:36)
+    at app//org.junit.jupiter.api.AssertTrue.assertTrue(AssertTrue.java:31)
+    at app//org.junit.jupiter.api.Assertions.assertTrue(Assertions.java:183)
+    at app//org.parser.ParserTest.foo(ParserTest.java:79)",
+                ),
             },
         ]);
 
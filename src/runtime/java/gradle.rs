@@ -3,14 +3,24 @@ use std::sync::mpsc::Receiver;
 use crate::{
     errors::FztError,
     runtime::{
-        Debugger, Runtime, RuntimeOutput, engine::Engine,
+        Debugger, Runtime, RuntimeConfig, RuntimeOutput, engine::Engine,
         java::formatter::gradle_formatter::GradleFormatter,
     },
 };
 use colored::Colorize;
 
+const TEST_FAILURE_EXIT_CODE: i32 = 1;
+
 #[derive(Default)]
-pub struct GradleRuntime {}
+pub struct GradleRuntime {
+    config: RuntimeConfig,
+}
+
+impl GradleRuntime {
+    pub fn new(config: RuntimeConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Runtime for GradleRuntime {
     fn run_tests(
@@ -32,9 +42,12 @@ impl Runtime for GradleRuntime {
             );
             return Ok(RuntimeOutput::new_empty());
         }
-        let mut engine = Engine::new(None, None);
-        // unbuffer merges stdout and stderr
-        engine.base_args(&["unbuffer", "./gradlew", "-i"]);
+        let mut engine = Engine::new(None, self.config.number_threads);
+        engine.config(&self.config);
+        engine.test_failure_exit_code(TEST_FAILURE_EXIT_CODE);
+        // The terminal merges stdout and stderr
+        engine.pty();
+        engine.base_args(&["./gradlew", "-i"]);
         engine.base_args_string(runtime_ags);
         engine.base_arg("test");
         let formatted_tests = tests
@@ -51,6 +64,11 @@ impl Runtime for GradleRuntime {
         )
     }
 
+    fn parallel_repetitions(&self) -> usize {
+        // The tests run in a single process
+        1
+    }
+
     fn name(&self) -> String {
         String::from("gradle")
     }
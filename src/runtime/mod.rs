@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, process::ExitStatus, time::Duration};
 
 use engine::EngineOutput;
 use serde::{Deserialize, Serialize};
@@ -6,6 +6,9 @@ use std::sync::mpsc::Receiver;
 
 use crate::errors::FztError;
 
+use dry_run::DryRun;
+
+pub mod dry_run;
 mod engine;
 pub mod java;
 mod process;
@@ -36,6 +39,57 @@ pub enum Debugger {
     Select,
 }
 
+/// Settings shared by every runtime, e.g. loaded from the project config.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RuntimeConfig {
+    pub envs: HashMap<String, String>,
+    pub number_threads: Option<usize>,
+    pub dry_run: Option<DryRun>,
+    // Seconds a single test item may run. Runtimes running all items in one process
+    // allow the sum for the whole run
+    pub timeout: Option<u64>,
+    // Seconds the whole run may take
+    pub batch_timeout: Option<u64>,
+}
+
+/// Outcome of a test run. Ordered by severity, so merging runs keeps the worst status.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RunStatus {
+    Passed,
+    TestsFailed,
+    RuntimeError,
+}
+
+impl RunStatus {
+    pub fn from_exit_status(
+        status: Option<ExitStatus>,
+        test_failure_exit_code: i32,
+        failed_tests: bool,
+    ) -> Self {
+        match status {
+            Some(status) if status.success() => {
+                if failed_tests {
+                    RunStatus::TestsFailed
+                } else {
+                    RunStatus::Passed
+                }
+            }
+            Some(status) if status.code() == Some(test_failure_exit_code) => RunStatus::TestsFailed,
+            Some(_) => RunStatus::RuntimeError,
+            // Process got stopped
+            None => RunStatus::Passed,
+        }
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            RunStatus::Passed => 0,
+            RunStatus::TestsFailed => 1,
+            RunStatus::RuntimeError => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd)]
 pub struct FailedTest {
     pub name: String,
@@ -49,6 +103,49 @@ impl FailedTest {
             error_msg: error_msg.to_string(),
         }
     }
+
+    pub fn timed_out(name: &str, timeout: Duration) -> Self {
+        Self::new(
+            name,
+            format!("timed out after {}s", timeout.as_secs()).as_str(),
+        )
+    }
+
+    /// The test had no result when the run of all tests was killed
+    pub fn batch_timed_out(name: &str, timeout: Duration) -> Self {
+        Self::new(
+            name,
+            format!("batch timed out after {}s", timeout.as_secs()).as_str(),
+        )
+    }
+}
+
+/// Result of a single test in a run
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    // Name as reported by the runtime, same as the runtime argument of the test
+    pub name: String,
+    pub status: TestStatus,
+    // Seconds, `None` if the runtime does not report it
+    pub duration: Option<f64>,
+}
+
+impl TestResult {
+    pub fn new(name: &str, status: TestStatus, duration: Option<f64>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            duration,
+        }
+    }
 }
 
 pub trait OutputFormatter {
@@ -60,32 +157,49 @@ pub trait OutputFormatter {
     fn skipped(&self) -> bool;
     fn reset_coverage(&mut self);
     fn failed_tests(&self) -> Vec<FailedTest>;
+    fn test_results(&self) -> Vec<TestResult>;
     fn update(&mut self) -> Result<(), FztError>;
     fn print(&self);
 }
 
 pub struct RuntimeOutput {
     pub failed_tests: Vec<FailedTest>,
+    pub test_results: Vec<TestResult>,
     pub output: Option<String>,
     pub coverage: HashMap<String, Vec<String>>,
+    pub status: RunStatus,
+    // Tests killed by a timeout, they are also part of `failed_tests`
+    pub timed_out: Vec<String>,
+    // Output of each item that did not pass, for runtimes starting one process per item.
+    // `output` only holds the output of passing items for them
+    pub failed_output: Vec<(String, String)>,
 }
 
 impl RuntimeOutput {
     pub fn new_empty() -> Self {
         Self {
             failed_tests: vec![],
+            test_results: vec![],
             output: None,
             coverage: HashMap::new(),
+            status: RunStatus::Passed,
+            timed_out: vec![],
+            failed_output: vec![],
         }
     }
 
     pub fn from_engine_output<F: OutputFormatter + Clone + Sync + Send + Default>(
         engine_output: &EngineOutput<F>,
+        test_failure_exit_code: i32,
     ) -> Self {
         Self {
             failed_tests: engine_output.failed_tests(),
+            test_results: engine_output.test_results(),
             output: Some(engine_output.merge_stdout()),
             coverage: engine_output.coverage(),
+            status: engine_output.status(test_failure_exit_code),
+            timed_out: engine_output.timed_out(),
+            failed_output: engine_output.failed_stdout(),
         }
     }
 }
@@ -100,5 +214,50 @@ pub trait Runtime {
         receiver: Option<Receiver<String>>,
         run_coverage: bool,
     ) -> Result<RuntimeOutput, FztError>;
+    /// Number of copies of the tests that can run at the same time, used to repeat tests.
+    /// Runtimes starting one process per test spread them over the threads of the engine.
+    fn parallel_repetitions(&self) -> usize;
     fn name(&self) -> String;
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use std::os::unix::process::ExitStatusExt;
+
+    use super::*;
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> Option<ExitStatus> {
+        Some(ExitStatus::from_raw(code << 8))
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn run_status_from_exit_status() {
+        assert_eq!(
+            RunStatus::from_exit_status(exit_status(0), 101, false),
+            RunStatus::Passed
+        );
+        assert_eq!(
+            RunStatus::from_exit_status(exit_status(0), 101, true),
+            RunStatus::TestsFailed
+        );
+        assert_eq!(
+            RunStatus::from_exit_status(exit_status(101), 101, true),
+            RunStatus::TestsFailed
+        );
+        assert_eq!(
+            RunStatus::from_exit_status(exit_status(127), 101, false),
+            RunStatus::RuntimeError
+        );
+        assert_eq!(
+            RunStatus::from_exit_status(None, 101, false),
+            RunStatus::Passed
+        );
+        assert_eq!(
+            RunStatus::TestsFailed.max(RunStatus::RuntimeError),
+            RunStatus::RuntimeError
+        );
+    }
+}
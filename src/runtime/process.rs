@@ -1,9 +1,17 @@
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
 use std::{
-    io::{BufRead, BufReader},
-    process::{Command, ExitStatus, Stdio},
+    io::{BufRead, BufReader, Read},
+    process::{Child, Command, ExitStatus, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
-use crossbeam_channel::{Receiver as CrossbeamReceiver, TryRecvError as CrossbeamTryRecvError};
+use crossbeam_channel::{
+    Receiver as CrossbeamReceiver, RecvTimeoutError, Sender, TryRecvError as CrossbeamTryRecvError,
+    unbounded,
+};
+use portable_pty::{Child as PtyChild, CommandBuilder, PtySize, native_pty_system};
 use std::sync::mpsc::{Receiver as StdReceiver, TryRecvError as StdTryRecvError};
 
 use crate::errors::FztError;
@@ -13,6 +21,8 @@ use super::OutputFormatter;
 #[derive(Clone, Debug)]
 pub struct CaptureOutput {
     pub stopped: bool,
+    // The child was killed because the deadline passed
+    pub timed_out: bool,
     pub stdout: String,
     pub stderr: String,
     pub status: Option<ExitStatus>,
@@ -71,70 +81,380 @@ fn check_stop_run<R: StringReceiver>(receiver: &Option<R>) -> Result<bool, FztEr
     Ok(false)
 }
 
-pub fn run_and_capture_print<F, R>(
-    mut cmd: Command,
+enum CapturedLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+// Cancellation is checked at least this often, also if the child is silent
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn forward_lines<S: Read + Send + 'static>(
+    stream: S,
+    sender: Sender<Result<CapturedLine, std::io::Error>>,
+    captured_line: fn(String) -> CapturedLine,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buffer = vec![];
+        loop {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buffer)
+                        .trim_end_matches(['\n', '\r'])
+                        .to_string();
+                    // The receiver is gone if the run was stopped
+                    if sender.send(Ok(captured_line(line))).is_err() {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    let _ = sender.send(Err(error));
+                    break;
+                }
+            }
+        }
+    })
+}
+
+// Passes the lines to the formatter until both streams are closed,
+// the run is stopped or the deadline passed
+fn capture_lines<F, R>(
+    lines: &CrossbeamReceiver<Result<CapturedLine, std::io::Error>>,
     formatter: &mut F,
-    receiver: Option<R>,
+    receiver: &Option<R>,
+    deadline: Option<Instant>,
 ) -> Result<CaptureOutput, FztError>
 where
     F: OutputFormatter,
     R: StringReceiver,
 {
-    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
-
     let mut stdout_output = String::new();
-
+    let mut stderr_output = String::new();
     let mut stopped = false;
+    let mut timed_out = false;
 
-    if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines() {
-            if check_stop_run(&receiver)? {
-                stopped = true;
-                break;
-            }
-            let line = line?;
-            formatter.line(&line)?;
-            stdout_output.push_str(&line);
-            stdout_output.push('\n');
+    loop {
+        if check_stop_run(receiver)? {
+            stopped = true;
+            break;
         }
-    }
-
-    let mut stderr_output = String::new();
-
-    if !stopped {
-        if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if check_stop_run(&receiver)? {
-                    stopped = true;
-                    break;
-                }
-                let line = line?;
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            timed_out = true;
+            break;
+        }
+        match lines.recv_timeout(STOP_POLL_INTERVAL) {
+            Ok(Ok(CapturedLine::Stdout(line))) => {
+                formatter.line(&line)?;
+                stdout_output.push_str(&line);
+                stdout_output.push('\n');
+            }
+            Ok(Ok(CapturedLine::Stderr(line))) => {
                 formatter.err_line(&line)?;
                 stderr_output.push_str(&line);
                 stderr_output.push('\n');
             }
+            Ok(Err(error)) => return Err(FztError::from(error)),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
         }
     }
 
-    let status = if stopped {
-        child.kill()?;
-        None
-    } else {
-        Some(child.wait()?)
-    };
+    Ok(CaptureOutput {
+        stopped,
+        timed_out,
+        stdout: stdout_output,
+        stderr: stderr_output,
+        status: None,
+    })
+}
+
+fn finish_capture<F: OutputFormatter>(
+    formatter: &mut F,
+    captured: CaptureOutput,
+    status: Option<ExitStatus>,
+) -> Result<CaptureOutput, FztError> {
     formatter.update()?;
     formatter.print();
-    let stdout_plain = String::from_utf8(strip_ansi_escapes::strip(stdout_output.as_bytes()))
+    let stdout_plain = String::from_utf8(strip_ansi_escapes::strip(captured.stdout.as_bytes()))
         .map_err(|e| FztError::from(e))?;
-    let stderr_plain = String::from_utf8(strip_ansi_escapes::strip(stderr_output.as_bytes()))
+    let stderr_plain = String::from_utf8(strip_ansi_escapes::strip(captured.stderr.as_bytes()))
         .map_err(|e| FztError::from(e))?;
 
     Ok(CaptureOutput {
-        stopped,
+        stopped: captured.stopped,
+        timed_out: captured.timed_out,
         stdout: stdout_plain,
         stderr: stderr_plain,
         status,
     })
 }
+
+// Kills the child and every process it started, e.g. the test binary started by cargo.
+// The child leads its own process group.
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) -> Result<(), FztError> {
+    // SAFETY: `killpg` has no memory safety requirements
+    let killed = unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } == 0;
+    if !killed {
+        let _ = child.kill();
+    }
+    child.wait()?;
+    Ok(())
+}
+
+// Without process groups only the child itself is killed
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) -> Result<(), FztError> {
+    let _ = child.kill();
+    child.wait()?;
+    Ok(())
+}
+
+/// Runs the command and passes stdout and stderr line by line to the formatter.
+/// Both streams are drained concurrently, lines are passed in the order they arrive.
+/// The processes of the child are killed as soon as a message is received on `receiver`
+/// or the deadline passed.
+pub fn run_and_capture_print<F, R>(
+    mut cmd: Command,
+    formatter: &mut F,
+    receiver: Option<R>,
+    deadline: Option<Instant>,
+) -> Result<CaptureOutput, FztError>
+where
+    F: OutputFormatter,
+    R: StringReceiver,
+{
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd.spawn()?;
+
+    let (sender, lines) = unbounded();
+    let mut readers = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward_lines(stdout, sender.clone(), CapturedLine::Stdout));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward_lines(stderr, sender.clone(), CapturedLine::Stderr));
+    }
+    // Only the readers hold senders, the channel disconnects once both streams are closed
+    drop(sender);
+
+    let captured = match capture_lines(&lines, formatter, &receiver, deadline) {
+        Ok(captured) => captured,
+        Err(error) => {
+            let _ = kill_process_group(&mut child);
+            return Err(error);
+        }
+    };
+    let status = if captured.stopped || captured.timed_out {
+        kill_process_group(&mut child)?;
+        None
+    } else {
+        readers.into_iter().for_each(|reader| {
+            let _ = reader.join();
+        });
+        Some(child.wait()?)
+    };
+    finish_capture(formatter, captured, status)
+}
+
+// The output of programs that query the terminal width should not be wrapped
+const PTY_SIZE: PtySize = PtySize {
+    rows: 50,
+    cols: 250,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+fn pty_error<E: std::fmt::Display>(error: E) -> FztError {
+    FztError::RuntimeError(format!("Pseudo terminal failed: {}", error))
+}
+
+fn command_builder(cmd: &Command) -> Result<CommandBuilder, FztError> {
+    let mut builder = CommandBuilder::new(cmd.get_program());
+    builder.args(cmd.get_args());
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => builder.env(key, value),
+            None => builder.env_remove(key),
+        }
+    }
+    // Without a directory the command would run in the home directory
+    match cmd.get_current_dir() {
+        Some(dir) => builder.cwd(dir),
+        None => builder.cwd(std::env::current_dir()?),
+    }
+    Ok(builder)
+}
+
+// Reading the pty fails with EIO once the child and all its descendants closed it
+struct PtyReader(Box<dyn Read + Send>);
+
+impl Read for PtyReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf) {
+            Err(error) if error.raw_os_error() == Some(libc::EIO) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+/// Like `run_and_capture_print`, but the command runs in a pseudo terminal, so it
+/// colors and line buffers its output as in an interactive shell. The terminal merges
+/// stdout and stderr, all lines are passed to the formatter as stdout lines.
+pub fn run_and_capture_print_pty<F, R>(
+    cmd: Command,
+    formatter: &mut F,
+    receiver: Option<R>,
+    deadline: Option<Instant>,
+) -> Result<CaptureOutput, FztError>
+where
+    F: OutputFormatter,
+    R: StringReceiver,
+{
+    let pair = native_pty_system().openpty(PTY_SIZE).map_err(pty_error)?;
+    let mut pty_child = pair
+        .slave
+        .spawn_command(command_builder(&cmd)?)
+        .map_err(pty_error)?;
+    // The child starts a new session, so it leads its own process group.
+    // The reader only reaches the end if no handle of the terminal is left open here
+    drop(pair.slave);
+    let reader = PtyReader(pair.master.try_clone_reader().map_err(pty_error)?);
+    // On unix the child is a std process, waiting on it gives the exact exit status
+    let pty_child: &mut dyn PtyChild = pty_child.as_mut();
+    let child = pty_child
+        .downcast_mut::<Child>()
+        .ok_or(FztError::RuntimeError(
+            "Pseudo terminal is not supported on this platform".to_string(),
+        ))?;
+
+    let (sender, lines) = unbounded();
+    let reader = forward_lines(reader, sender, CapturedLine::Stdout);
+
+    let captured = match capture_lines(&lines, formatter, &receiver, deadline) {
+        Ok(captured) => captured,
+        Err(error) => {
+            let _ = kill_process_group(child);
+            return Err(error);
+        }
+    };
+    let status = if captured.stopped || captured.timed_out {
+        kill_process_group(child)?;
+        None
+    } else {
+        let _ = reader.join();
+        Some(child.wait()?)
+    };
+    finish_capture(formatter, captured, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crossbeam_channel::Receiver;
+
+    use crate::runtime::rust::cargo::formatter::CargoFormatter;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn capture_both_streams() {
+        // More stderr output than fits into the pipe buffer
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("i=0; while [ $i -lt 20000 ]; do echo error $i >&2; i=$((i+1)); done; echo done");
+        let output = run_and_capture_print(
+            cmd,
+            &mut CargoFormatter::new(),
+            None::<Receiver<String>>,
+            None,
+        )
+        .unwrap();
+        assert!(!output.stopped);
+        assert!(output.status.unwrap().success());
+        assert_eq!(output.stdout, "done\n");
+        assert_eq!(output.stderr.lines().count(), 20000);
+        assert_eq!(output.stderr.lines().last(), Some("error 19999"));
+    }
+
+    #[test]
+    fn capture_pty() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("test -t 1 && echo tty; echo error >&2; exit 3");
+        let output = run_and_capture_print_pty(
+            cmd,
+            &mut CargoFormatter::new(),
+            None::<Receiver<String>>,
+            None,
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "tty\nerror\n");
+        assert_eq!(output.stderr, "");
+        assert_eq!(output.status.unwrap().code(), Some(3));
+    }
+
+    #[test]
+    fn stop_silent_child() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("30");
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            sender.send("stop".to_string()).unwrap();
+        });
+        let start = Instant::now();
+        let output =
+            run_and_capture_print(cmd, &mut CargoFormatter::new(), Some(receiver), None).unwrap();
+        assert!(output.stopped);
+        assert!(output.status.is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn kill_on_timeout_pipe() {
+        // The grandchild holds the pipes open until it is killed as well
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo started; sleep 30; echo finished");
+        let start = Instant::now();
+        let output = run_and_capture_print(
+            cmd,
+            &mut CargoFormatter::new(),
+            None::<Receiver<String>>,
+            Some(Instant::now() + Duration::from_millis(300)),
+        )
+        .unwrap();
+        assert!(output.timed_out);
+        assert!(!output.stopped);
+        assert!(output.status.is_none());
+        assert_eq!(output.stdout, "started\n");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn kill_on_timeout_pty() {
+        // The grandchild holds the pipes open until it is killed as well
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo started; sleep 30; echo finished");
+        let start = Instant::now();
+        let output = run_and_capture_print_pty(
+            cmd,
+            &mut CargoFormatter::new(),
+            None::<Receiver<String>>,
+            Some(Instant::now() + Duration::from_millis(300)),
+        )
+        .unwrap();
+        assert!(output.timed_out);
+        assert!(!output.stopped);
+        assert!(output.status.is_none());
+        assert_eq!(output.stdout, "started\n");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -2,13 +2,14 @@ use std::{collections::HashSet, fs, path::PathBuf};
 
 use crate::{
     FztError,
-    runtime::{FailedTest, OutputFormatter, python::test_report::TestReport},
+    runtime::{FailedTest, OutputFormatter, TestResult, python::test_report::TestReport},
 };
 use colored::Colorize;
 
 #[derive(Clone, Debug, Default)]
 pub struct PytestFormatter {
     failed_tests: HashSet<FailedTest>,
+    test_results: Vec<TestResult>,
     temp_report_log_path: PathBuf,
 }
 
@@ -17,6 +18,7 @@ impl PytestFormatter {
         Self {
             temp_report_log_path,
             failed_tests: HashSet::new(),
+            test_results: vec![],
         }
     }
 }
@@ -50,6 +52,10 @@ impl OutputFormatter for PytestFormatter {
         self.failed_tests.iter().cloned().collect()
     }
 
+    fn test_results(&self) -> Vec<TestResult> {
+        self.test_results.clone()
+    }
+
     fn update(&mut self) -> Result<(), FztError> {
         if !self.temp_report_log_path.exists() {
             println!(
@@ -63,15 +69,13 @@ impl OutputFormatter for PytestFormatter {
         }
         let json_str = fs::read_to_string(&self.temp_report_log_path)?;
         let report: TestReport = serde_json::from_str(&json_str)?;
+        self.test_results
+            .extend(report.tests.iter().filter_map(|test| test.result()));
         report.tests.iter().for_each(|test| {
             if test.outcome == "failed" {
                 self.failed_tests.insert(FailedTest {
                     name: test.nodeid.clone(),
-                    error_msg: test
-                        .call
-                        .as_ref()
-                        .and_then(|call| call.crash.as_ref())
-                        .map_or(String::new(), |crash| crash.message.clone()),
+                    error_msg: test.failure_output(),
                 });
             }
         });
@@ -84,6 +88,7 @@ impl OutputFormatter for PytestFormatter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::TestStatus;
     use pretty_assertions::assert_eq;
     use std::fs;
     use tempfile::TempDir;
@@ -248,9 +253,9 @@ mod tests {
                 .any(|t| t.name == "test_file.py::test_failed_2")
         );
 
-        // Verify error messages are captured
+        // Verify tracebacks are captured
         for failed_test in &failed_tests {
-            assert_eq!(failed_test.error_msg, "assertion failed");
+            assert_eq!(failed_test.error_msg, "AssertionError: Test failed");
         }
     }
 
@@ -285,4 +290,29 @@ mod tests {
         // Should have no failed tests
         assert_eq!(formatter.failed_tests().len(), 0);
     }
+
+    #[test]
+    fn collect_test_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let report_path = temp_dir.path().join("report.json");
+        fs::write(&report_path, create_test_report_json(1, 1, 1, 1.0)).unwrap();
+
+        let mut formatter = PytestFormatter::new(report_path);
+        formatter.update().unwrap();
+
+        let results = formatter.test_results();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.name.as_str(), result.status))
+                .collect::<Vec<(&str, TestStatus)>>(),
+            vec![
+                ("test_file.py::test_passed_0", TestStatus::Passed),
+                ("test_file.py::test_failed_0", TestStatus::Failed),
+                ("test_file.py::test_skipped_0", TestStatus::Skipped),
+            ]
+        );
+        // Setup, call and teardown
+        assert!((results[0].duration.unwrap() - 0.12).abs() < 1e-9);
+    }
 }
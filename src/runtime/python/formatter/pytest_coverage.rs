@@ -5,7 +5,7 @@ use colored::Colorize;
 use crate::{
     FztError,
     runtime::{
-        FailedTest, OutputFormatter,
+        FailedTest, OutputFormatter, TestResult,
         python::{coverage_report::CoverageReport, test_report::TestReport},
     },
 };
@@ -13,6 +13,7 @@ use crate::{
 #[derive(Clone, Debug, Default)]
 pub struct PytestCovFormatter {
     failed_tests: HashSet<FailedTest>,
+    test_results: Vec<TestResult>,
     skipped_test: HashSet<String>,
     passed_tests: HashSet<String>,
     output: String,
@@ -31,6 +32,7 @@ impl PytestCovFormatter {
     pub fn new(temp_cov_path: PathBuf, temp_report_log_path: PathBuf, formatter_id: &str) -> Self {
         Self {
             failed_tests: HashSet::new(),
+            test_results: vec![],
             skipped_test: HashSet::new(),
             passed_tests: HashSet::new(),
             temp_cov_path,
@@ -72,6 +74,8 @@ impl PytestCovFormatter {
         }
 
         self.duration = report.duration;
+        self.test_results
+            .extend(report.tests.iter().filter_map(|test| test.result()));
 
         report.tests.iter().for_each(|test| {
             self.output.push_str(&test.nodeid);
@@ -79,11 +83,7 @@ impl PytestCovFormatter {
             if test.outcome == "failed" {
                 self.failed_tests.insert(FailedTest {
                     name: test.nodeid.clone(),
-                    error_msg: test
-                        .call
-                        .as_ref()
-                        .and_then(|call| call.crash.as_ref())
-                        .map_or(String::new(), |crash| crash.message.clone()),
+                    error_msg: test.failure_output(),
                 });
                 self.output.push_str(&"FAILED".red().bold().to_string());
             } else if test.outcome == "skipped" {
@@ -162,6 +162,7 @@ impl OutputFormatter for PytestCovFormatter {
 
     fn add(&mut self, other: &Self) {
         self.failed_tests.extend(other.failed_tests.clone());
+        self.test_results.extend(other.test_results.clone());
         self.skipped_test.extend(other.skipped_test.clone());
         self.passed += other.passed;
         self.failed += other.failed;
@@ -206,6 +207,10 @@ impl OutputFormatter for PytestCovFormatter {
         self.failed_tests.iter().cloned().collect()
     }
 
+    fn test_results(&self) -> Vec<TestResult> {
+        self.test_results.clone()
+    }
+
     fn update(&mut self) -> Result<(), FztError> {
         self.process_test_report()?;
         self.process_coverage_report()
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::mpsc::Receiver};
+use std::{collections::HashMap, process::Command, sync::mpsc::Receiver};
 
 use itertools::Itertools;
 use tempfile::TempDir;
@@ -6,15 +6,33 @@ use tempfile::TempDir;
 use crate::{
     errors::FztError,
     runtime::{
-        Debugger, OutputFormatter, PythonDebugger, Runtime, RuntimeOutput,
+        Debugger, OutputFormatter, PythonDebugger, Runtime, RuntimeConfig, RuntimeOutput,
         engine::{Engine, TestItem},
     },
 };
 
 use super::formatter::{pytest::PytestFormatter, pytest_coverage::PytestCovFormatter};
 
+const TEST_FAILURE_EXIT_CODE: i32 = 1;
+
+// The pytest-timeout plugin kills single tests with `--timeout`
+fn pytest_timeout_installed() -> bool {
+    Command::new("python")
+        .args(["-c", "import pytest_timeout"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
 #[derive(Default)]
-pub struct PytestRuntime {}
+pub struct PytestRuntime {
+    config: RuntimeConfig,
+}
+
+impl PytestRuntime {
+    pub fn new(config: RuntimeConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Runtime for PytestRuntime {
     fn run_tests(
@@ -29,7 +47,7 @@ impl Runtime for PytestRuntime {
         let mut base_args = if debugger.is_some() || runtime_ags.contains(&String::from("--pdb")) {
             vec!["python", "-m", "pytest", "-s"]
         } else {
-            vec!["unbuffer", "python", "-m", "pytest"]
+            vec!["python", "-m", "pytest"]
         };
 
         let ordered_tests: Vec<String> = tests
@@ -134,21 +152,30 @@ impl Runtime for PytestRuntime {
                     }
                 })
                 .collect();
-            let mut engine = Engine::new(None, None);
+            let mut engine = Engine::new(None, self.config.number_threads);
+            engine.pty();
+            engine.config(&self.config);
+            engine.test_failure_exit_code(TEST_FAILURE_EXIT_CODE);
             engine.base_args(base_args.as_slice());
             engine.runtime_args(runtime_ags);
             engine.base_args(&["--cov=myapp", "--cov-report=term-missing:skip-covered"]);
             let engine_output = engine.execute_per_item_parallel(receiver, test_items, verbose)?;
+            if self.config.dry_run.is_some() {
+                return Ok(RuntimeOutput::new_empty());
+            }
 
             engine_output.merge_formatters().finish();
 
             if engine_output.stopped() {
                 Ok(RuntimeOutput::new_empty())
             } else {
-                Ok(RuntimeOutput::from_engine_output(&engine_output))
+                Ok(RuntimeOutput::from_engine_output(
+                    &engine_output,
+                    TEST_FAILURE_EXIT_CODE,
+                ))
             }
         } else {
-            let mut engine = Engine::new(None, None);
+            let mut engine = Engine::new(None, self.config.number_threads);
             let rep_dir = tempfile::tempdir()?;
             let rep_path = rep_dir.path().join("report.json").to_path_buf();
             let rep_arg = format!(
@@ -160,11 +187,22 @@ impl Runtime for PytestRuntime {
             );
             base_args.push("--json-report");
             base_args.push(rep_arg.as_str());
+            let debug_mode = debugger.is_some() || runtime_ags.contains(&String::from("--pdb"));
+            // Debug sessions inherit the terminal and are not captured
+            engine.pty();
             engine.base_args(base_args.as_slice());
+            if let Some(timeout) = self.config.timeout.filter(|_| !debug_mode)
+                && pytest_timeout_installed()
+            {
+                engine.base_args_string(&[format!("--timeout={timeout}")]);
+                engine.runtime_item_timeout();
+            }
             engine.runtime_args(runtime_ags);
+            engine.config(&self.config);
+            engine.test_failure_exit_code(TEST_FAILURE_EXIT_CODE);
             engine.envs(&envs);
             engine.execute_single_batch_sequential(
-                debugger.is_some() || runtime_ags.contains(&String::from("--pdb")),
+                debug_mode,
                 receiver,
                 ordered_tests,
                 &mut PytestFormatter::new(rep_path),
@@ -173,6 +211,11 @@ impl Runtime for PytestRuntime {
         }
     }
 
+    fn parallel_repetitions(&self) -> usize {
+        // The tests run in a single process
+        1
+    }
+
     fn name(&self) -> String {
         String::from("pytest")
     }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::runtime::{TestResult, TestStatus};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestReport {
     pub created: f64,
@@ -49,6 +51,33 @@ pub struct Test {
     pub teardown: TestPhase,
 }
 
+impl Test {
+    /// Outcome of the test, the duration includes setup and teardown
+    pub fn result(&self) -> Option<TestResult> {
+        let status = match self.outcome.as_str() {
+            "passed" | "xpassed" => TestStatus::Passed,
+            "failed" | "error" => TestStatus::Failed,
+            "skipped" | "xfailed" => TestStatus::Skipped,
+            _ => return None,
+        };
+        let duration = self.setup.duration
+            + self.call.as_ref().map_or(0f64, |call| call.duration)
+            + self.teardown.duration;
+        Some(TestResult::new(&self.nodeid, status, Some(duration)))
+    }
+
+    /// Traceback of a failed call, the crash message if pytest did not report one
+    pub fn failure_output(&self) -> String {
+        self.call.as_ref().map_or(String::new(), |call| {
+            call.longrepr.clone().unwrap_or_else(|| {
+                call.crash
+                    .as_ref()
+                    .map_or(String::new(), |crash| crash.message.clone())
+            })
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestPhase {
     pub duration: f64,
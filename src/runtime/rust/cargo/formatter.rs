@@ -2,17 +2,19 @@ use colored::Colorize;
 
 use crate::{
     FztError,
-    runtime::{FailedTest, OutputFormatter},
+    runtime::{FailedTest, OutputFormatter, TestResult, TestStatus},
 };
 
 const TEST_PREFIX: &str = "test ";
+const TEST_PASSED_SUFFIX: &str = " ... ok";
+const TEST_IGNORED_SUFFIX: &str = " ... ignored";
 const TEST_FAILED_SUFFIX: &str = " ... FAILED";
 const FAILURES_HEADER: &str = "failures:";
 const RUNNING_HEADER: &str = "running 1 test";
 
-fn extract_test_name(line: &str) -> Option<&str> {
+fn extract_test_name<'a>(line: &'a str, suffix: &str) -> Option<&'a str> {
     let start_idx = line.find(TEST_PREFIX)? + TEST_PREFIX.len();
-    let end_idx = line.find(TEST_FAILED_SUFFIX)?;
+    let end_idx = line.rfind(suffix)?;
     Some(line[start_idx..end_idx].trim())
 }
 
@@ -44,6 +46,7 @@ fn parse_cargo_time(line: &str) -> Option<f64> {
 #[derive(Clone, Default)]
 pub struct CargoFormatter {
     failed_tests: Vec<FailedTest>,
+    test_results: Vec<TestResult>,
     passed: usize,
     failed: usize,
     ignored: usize,
@@ -53,12 +56,15 @@ pub struct CargoFormatter {
     seconds: f64,
     coverage: Vec<String>,
     print_output: String,
+    // Index of the first result of the test binary currently running
+    block_start: usize,
 }
 
 impl CargoFormatter {
     pub fn new() -> Self {
         Self {
             failed_tests: vec![],
+            test_results: vec![],
             passed: 0,
             failed: 0,
             ignored: 0,
@@ -68,6 +74,16 @@ impl CargoFormatter {
             seconds: 0f64,
             coverage: vec![],
             print_output: String::new(),
+            block_start: 0,
+        }
+    }
+}
+
+impl CargoFormatter {
+    fn push_result(&mut self, plain_line: &str, suffix: &str, status: TestStatus) {
+        if let Some(test_name) = extract_test_name(plain_line, suffix) {
+            self.test_results
+                .push(TestResult::new(test_name, status, None));
         }
     }
 }
@@ -77,6 +93,11 @@ impl OutputFormatter for CargoFormatter {
         let plain_bytes = strip_ansi_escapes::strip(line.as_bytes());
         let plain_line = String::from_utf8(plain_bytes).map_err(FztError::from)?;
 
+        // Start of the tests of the next binary
+        if plain_line.starts_with("running ") {
+            self.block_start = self.test_results.len();
+        }
+
         // Start running
         if plain_line == RUNNING_HEADER && !self.running {
             self.running = true;
@@ -102,16 +123,18 @@ impl OutputFormatter for CargoFormatter {
         }
 
         // Test Passed
-        if plain_line.ends_with("... ok") {
+        if plain_line.ends_with(TEST_PASSED_SUFFIX) {
             self.print_output.push_str(line);
             self.passed += 1;
+            self.push_result(&plain_line, TEST_PASSED_SUFFIX, TestStatus::Passed);
             return Ok(());
         }
 
         // Test Ignored
-        if plain_line.ends_with("... ignored") {
+        if plain_line.ends_with(TEST_IGNORED_SUFFIX) {
             self.print_output.push_str(line);
             self.ignored += 1;
+            self.push_result(&plain_line, TEST_IGNORED_SUFFIX, TestStatus::Skipped);
             return Ok(());
         }
 
@@ -126,7 +149,8 @@ impl OutputFormatter for CargoFormatter {
         if plain_line.ends_with(TEST_FAILED_SUFFIX) {
             self.print_output.push_str(line);
             self.failed += 1;
-            if let Some(test_name) = extract_test_name(&plain_line) {
+            self.push_result(&plain_line, TEST_FAILED_SUFFIX, TestStatus::Failed);
+            if let Some(test_name) = extract_test_name(&plain_line, TEST_FAILED_SUFFIX) {
                 self.failed_tests.push(FailedTest::new(test_name, ""));
             } else {
                 self.failed_tests
@@ -155,6 +179,13 @@ impl OutputFormatter for CargoFormatter {
         // Parse Time
         if let Some(secs) = parse_cargo_time(&plain_line) {
             self.seconds += secs;
+            // Libtest only reports the time per binary, which is the test time if only one test ran
+            if let [result] = &mut self.test_results[self.block_start..]
+                && result.duration.is_none()
+            {
+                result.duration = Some(secs);
+            }
+            self.block_start = self.test_results.len();
         }
 
         Ok(())
@@ -165,6 +196,7 @@ impl OutputFormatter for CargoFormatter {
     }
     fn add(&mut self, other: &CargoFormatter) {
         self.failed_tests.extend(other.failed_tests.clone());
+        self.test_results.extend(other.test_results.clone());
         self.passed += other.passed;
         self.failed += other.failed;
         self.seconds += other.seconds;
@@ -217,6 +249,10 @@ impl OutputFormatter for CargoFormatter {
         self.failed_tests.clone()
     }
 
+    fn test_results(&self) -> Vec<TestResult> {
+        self.test_results.clone()
+    }
+
     fn print(&self) {
         println!("{}", self.print_output);
     }
@@ -231,7 +267,7 @@ impl OutputFormatter for CargoFormatter {
 
 #[cfg(test)]
 mod tests {
-    use crate::runtime::{FailedTest, OutputFormatter};
+    use crate::runtime::{FailedTest, OutputFormatter, TestResult, TestStatus};
 
     use super::CargoFormatter;
 
@@ -329,4 +365,48 @@ error: test failed, to rerun pass `--lib
         }
         assert_eq!(formatter.failed_tests, expected);
     }
+
+    #[test]
+    fn collect_test_results() {
+        let output = "
+running 1 test
+test tests::alpha ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 3 filtered out; finished in 0.25s
+
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+running 2 tests
+test tests::delta ... ok
+test tests::epsilon ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.50s
+
+running 1 test
+test integration::zeta ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.75s
+        ";
+        let mut formatter = CargoFormatter::new();
+        for line in output.lines() {
+            formatter.line(line).unwrap();
+        }
+        let mut other = CargoFormatter::new();
+        other.line("test tests::beta ... FAILED").unwrap();
+        other.line("test tests::gamma ... ignored").unwrap();
+        formatter.add(&other);
+        assert_eq!(
+            formatter.test_results(),
+            vec![
+                TestResult::new("tests::alpha", TestStatus::Passed, Some(0.25)),
+                TestResult::new("tests::delta", TestStatus::Passed, None),
+                TestResult::new("tests::epsilon", TestStatus::Passed, None),
+                TestResult::new("integration::zeta", TestStatus::Passed, Some(0.75)),
+                TestResult::new("tests::beta", TestStatus::Failed, None),
+                TestResult::new("tests::gamma", TestStatus::Skipped, None),
+            ]
+        );
+    }
 }
@@ -1,2 +1,2 @@
-mod formatter;
+pub(crate) mod formatter;
 pub mod runtime;
@@ -3,15 +3,26 @@ use std::{collections::HashMap, sync::mpsc::Receiver as StdReceiver};
 use crate::{
     errors::FztError,
     runtime::{
-        Debugger, OutputFormatter, Runtime, RuntimeOutput,
-        engine::{Engine, TestItem},
+        Debugger, OutputFormatter, Runtime, RuntimeConfig, RuntimeOutput,
+        engine::{Engine, TestItem, number_threads},
     },
 };
 
 use super::formatter::CargoFormatter;
 
+const TEST_FAILURE_EXIT_CODE: i32 = 101;
+const TARPAULIN_FAILURE_EXIT_CODE: i32 = 1;
+
 #[derive(Default)]
-pub struct CargoRuntime {}
+pub struct CargoRuntime {
+    config: RuntimeConfig,
+}
+
+impl CargoRuntime {
+    pub fn new(config: RuntimeConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Runtime for CargoRuntime {
     fn run_tests(
@@ -36,30 +47,48 @@ impl Runtime for CargoRuntime {
                 }
             })
             .collect();
-        // unbuffer merges stdout and stderr
+        let test_failure_exit_code = if run_coverage {
+            TARPAULIN_FAILURE_EXIT_CODE
+        } else {
+            TEST_FAILURE_EXIT_CODE
+        };
         let mut engine = if run_coverage {
             // Coverage only work with one thread at a time.
             let mut engine = Engine::new(Some("--".to_string()), Some(1));
-            engine.base_args(&["unbuffer", "cargo", "tarpaulin", "--skip-clean", "--"]);
+            engine.base_args(&["cargo", "tarpaulin", "--skip-clean", "--"]);
             engine
         } else {
-            let mut engine = Engine::new(Some("--".to_string()), None);
-            engine.base_args(&["unbuffer", "cargo", "test"]);
+            let mut engine = Engine::new(Some("--".to_string()), self.config.number_threads);
+            engine.base_args(&["cargo", "test"]);
             engine
         };
+        // The terminal merges stdout and stderr
+        engine.pty();
+        engine.config(&self.config);
+        engine.test_failure_exit_code(test_failure_exit_code);
         engine.runtime_args(runtime_args);
 
         let engine_output = engine.execute_per_item_parallel(receiver, test_items, verbose)?;
+        if self.config.dry_run.is_some() {
+            return Ok(RuntimeOutput::new_empty());
+        }
 
         engine_output.merge_formatters().finish();
 
         if engine_output.stopped() {
             Ok(RuntimeOutput::new_empty())
         } else {
-            Ok(RuntimeOutput::from_engine_output(&engine_output))
+            Ok(RuntimeOutput::from_engine_output(
+                &engine_output,
+                test_failure_exit_code,
+            ))
         }
     }
 
+    fn parallel_repetitions(&self) -> usize {
+        number_threads(self.config.number_threads)
+    }
+
     fn name(&self) -> String {
         String::from("cargo")
     }
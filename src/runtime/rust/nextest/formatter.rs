@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::{
     FztError,
-    runtime::{FailedTest, OutputFormatter},
+    runtime::{FailedTest, OutputFormatter, TestResult, TestStatus},
 };
 
 use super::test_report::TestReport;
@@ -10,12 +10,14 @@ use super::test_report::TestReport;
 #[derive(Clone, Debug, Default)]
 pub struct NextestFormatter {
     failed_tests: HashSet<FailedTest>,
+    test_results: Vec<TestResult>,
 }
 
 impl NextestFormatter {
     pub fn new() -> Self {
         Self {
             failed_tests: HashSet::new(),
+            test_results: vec![],
         }
     }
 }
@@ -26,20 +28,30 @@ impl OutputFormatter for NextestFormatter {
         let plain_line = String::from_utf8(plain_bytes).map_err(FztError::from)?;
         if plain_line.starts_with("{\"type\":\"test\"") {
             let report: TestReport = serde_json::from_str(&plain_line)?;
+            let test_name_parts: Vec<&str> = report.name.splitn(2, "$").collect();
+            let test_name = if test_name_parts.len() == 2 {
+                test_name_parts[1]
+            } else {
+                report.name.as_str()
+            };
+            let status = match report.event.as_str() {
+                "ok" => Some(TestStatus::Passed),
+                "failed" => Some(TestStatus::Failed),
+                "ignored" => Some(TestStatus::Skipped),
+                _ => None,
+            };
+            if let Some(status) = status {
+                self.test_results
+                    .push(TestResult::new(test_name, status, report.exec_time));
+            }
             if report.event == "failed" {
-                let test_name_parts: Vec<&str> = report.name.splitn(2, "$").collect();
                 let err_msg = if let Some(msg) = report.stdout {
                     msg
                 } else {
                     "No output captured.".to_string()
                 };
-                if test_name_parts.len() == 2 {
-                    self.failed_tests
-                        .insert(FailedTest::new(test_name_parts[1], err_msg.as_str()));
-                } else {
-                    self.failed_tests
-                        .insert(FailedTest::new(report.name.as_str(), err_msg.as_str()));
-                }
+                self.failed_tests
+                    .insert(FailedTest::new(test_name, err_msg.as_str()));
             }
         }
         if !plain_line.starts_with("{\"type\"") {
@@ -61,6 +73,7 @@ impl OutputFormatter for NextestFormatter {
         for failed_test in &other.failed_tests {
             self.failed_tests.insert(failed_test.clone());
         }
+        self.test_results.extend(other.test_results.clone());
     }
 
     fn finish(self) {}
@@ -79,6 +92,10 @@ impl OutputFormatter for NextestFormatter {
         self.failed_tests.iter().cloned().collect()
     }
 
+    fn test_results(&self) -> Vec<TestResult> {
+        self.test_results.clone()
+    }
+
     fn update(&mut self) -> Result<(), FztError> {
         Ok(())
     }
@@ -96,7 +113,8 @@ mod tests {
  Nextest run ID a09c7739-fde2-489e-abb8-86cf0bee9aea with nextest profile: default
     Starting 23 tests across 2 binaries
         PASS [   1.513s] (23/23) FzT tests::java::java_test::tests::collect_tests
-{"type":"test","name":"Foo::Boo$tests::example_test::test_case_1","event":"failed","stdout":"panicked at"}
+{"type":"test","name":"Foo::Boo$tests::example_test::test_case_1","event":"failed","stdout":"panicked at","exec_time":0.5}
+{"type":"test","name":"Foo::Boo$tests::example_test::test_case_2","event":"ok","exec_time":0.25}
         "#;
         let expected = vec![FailedTest::new(
             "tests::example_test::test_case_1",
@@ -110,6 +128,21 @@ mod tests {
 
         let failed_tests = formatter.failed_tests();
         assert_eq!(failed_tests, expected);
+        assert_eq!(
+            formatter.test_results(),
+            vec![
+                TestResult::new(
+                    "tests::example_test::test_case_1",
+                    TestStatus::Failed,
+                    Some(0.5)
+                ),
+                TestResult::new(
+                    "tests::example_test::test_case_2",
+                    TestStatus::Passed,
+                    Some(0.25)
+                ),
+            ]
+        );
     }
 
     #[test]
@@ -1,15 +1,38 @@
 use crate::{
     errors::FztError,
     runtime::{
-        Debugger, Runtime, RuntimeOutput, engine::Engine,
+        Debugger, Runtime, RuntimeConfig, RuntimeOutput, engine::Engine,
         rust::nextest::formatter::NextestFormatter,
     },
 };
 use colored::Colorize;
-use std::{collections::HashMap, sync::mpsc::Receiver as StdReceiver};
+use std::{collections::HashMap, env, fs, path::PathBuf, sync::mpsc::Receiver as StdReceiver};
+
+const TEST_FAILURE_EXIT_CODE: i32 = 100;
+
+// Tool config terminating tests that run longer than `timeout` seconds.
+// The file name only depends on the timeout, so printed commands stay valid.
+fn timeout_config(timeout: u64) -> Result<PathBuf, FztError> {
+    let path = env::temp_dir().join(format!("fzt-nextest-timeout-{timeout}s.toml"));
+    fs::write(
+        &path,
+        format!(
+            "[profile.default]\nslow-timeout = {{ period = \"{timeout}s\", terminate-after = 1 }}\n"
+        ),
+    )?;
+    Ok(path)
+}
 
 #[derive(Default)]
-pub struct NextestRuntime {}
+pub struct NextestRuntime {
+    config: RuntimeConfig,
+}
+
+impl NextestRuntime {
+    pub fn new(config: RuntimeConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Runtime for NextestRuntime {
     fn run_tests(
@@ -22,7 +45,6 @@ impl Runtime for NextestRuntime {
         run_coverage: bool,
     ) -> Result<RuntimeOutput, FztError> {
         let base_args = vec![
-            "unbuffer",
             "cargo-nextest",
             "nextest",
             "run",
@@ -43,9 +65,20 @@ impl Runtime for NextestRuntime {
             return Ok(RuntimeOutput::new_empty());
         }
         let envs = HashMap::from([("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1")]);
-        let mut engine = Engine::new(Some("--".to_string()), None);
+        let mut engine = Engine::new(Some("--".to_string()), self.config.number_threads);
+        engine.pty();
+        engine.config(&self.config);
+        engine.test_failure_exit_code(TEST_FAILURE_EXIT_CODE);
         engine.envs(&envs);
         engine.base_args(base_args.as_slice());
+        if let Some(timeout) = self.config.timeout {
+            let config = timeout_config(timeout)?;
+            engine.base_args_string(&[
+                "--tool-config-file".to_string(),
+                format!("fzt:{}", config.display()),
+            ]);
+            engine.runtime_item_timeout();
+        }
         engine.runtime_args(runtime_args);
         engine.execute_single_batch_sequential(
             false,
@@ -56,6 +89,11 @@ impl Runtime for NextestRuntime {
         )
     }
 
+    fn parallel_repetitions(&self) -> usize {
+        // The tests run in a single process
+        1
+    }
+
     fn name(&self) -> String {
         String::from("nextest")
     }
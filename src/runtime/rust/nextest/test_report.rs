@@ -5,4 +5,5 @@ pub struct TestReport {
     pub event: String,
     pub name: String,
     pub stdout: Option<String>,
+    pub exec_time: Option<f64>,
 }
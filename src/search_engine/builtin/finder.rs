@@ -0,0 +1,235 @@
+use std::{cmp::Reverse, collections::BTreeSet};
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub index: usize,
+    pub score: i64,
+    // Char positions of the matched query characters, used for highlighting
+    pub positions: Vec<usize>,
+}
+
+/// State of the finder: query, matched items, cursor and selection
+pub struct Finder {
+    items: Vec<String>,
+    query: String,
+    matches: Vec<Match>,
+    cursor: usize,
+    selected: BTreeSet<usize>,
+    multi: bool,
+    matcher: SkimMatcherV2,
+}
+
+impl Finder {
+    pub fn new(items: Vec<String>, query: Option<&str>, multi: bool) -> Self {
+        let mut finder = Self {
+            items,
+            query: query.unwrap_or_default().to_string(),
+            matches: vec![],
+            cursor: 0,
+            selected: BTreeSet::new(),
+            multi,
+            matcher: SkimMatcherV2::default().smart_case(),
+        };
+        finder.update_matches();
+        finder
+    }
+
+    // Like fzf, every whitespace separated term of the query has to match
+    fn match_item(&self, item: &str) -> Option<(i64, Vec<usize>)> {
+        let mut score = 0;
+        let mut positions = BTreeSet::new();
+        for term in self.query.split_whitespace() {
+            let (term_score, term_positions) = self.matcher.fuzzy_indices(item, term)?;
+            score += term_score;
+            positions.extend(term_positions);
+        }
+        Some((score, positions.into_iter().collect()))
+    }
+
+    fn update_matches(&mut self) {
+        let mut matches: Vec<Match> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                self.match_item(item).map(|(score, positions)| Match {
+                    index,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // Stable sort keeps the input order for equal scores
+        if !self.query.trim().is_empty() {
+            matches.sort_by_key(|found| Reverse(found.score));
+        }
+        self.matches = matches;
+        self.cursor = 0;
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_multi(&self) -> bool {
+        self.multi
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.selected.len()
+    }
+
+    /// Item index under the cursor
+    pub fn current(&self) -> Option<usize> {
+        self.matches.get(self.cursor).map(|found| found.index)
+    }
+
+    pub fn push_char(&mut self, ch: char) {
+        self.query.push(ch);
+        self.update_matches();
+    }
+
+    pub fn pop_char(&mut self) {
+        if self.query.pop().is_some() {
+            self.update_matches();
+        }
+    }
+
+    pub fn clear_query(&mut self) {
+        self.query.clear();
+        self.update_matches();
+    }
+
+    pub fn move_up(&mut self, steps: usize) {
+        self.cursor = self.cursor.saturating_sub(steps);
+    }
+
+    pub fn move_down(&mut self, steps: usize) {
+        self.cursor = (self.cursor + steps).min(self.matches.len().saturating_sub(1));
+    }
+
+    pub fn toggle(&mut self) {
+        if !self.multi {
+            return;
+        }
+        if let Some(index) = self.current()
+            && !self.selected.remove(&index)
+        {
+            self.selected.insert(index);
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        if self.multi {
+            self.selected
+                .extend(self.matches.iter().map(|found| found.index));
+        }
+    }
+
+    pub fn deselect_all(&mut self) {
+        self.selected.clear();
+    }
+
+    pub fn toggle_all(&mut self) {
+        if !self.multi {
+            return;
+        }
+        for found in self.matches.iter() {
+            if !self.selected.remove(&found.index) {
+                self.selected.insert(found.index);
+            }
+        }
+    }
+
+    /// Indices of the selected items, or of the item under the cursor if nothing is selected
+    pub fn accept(&self) -> Vec<usize> {
+        if self.selected.is_empty() {
+            self.current().into_iter().collect()
+        } else {
+            self.selected.iter().copied().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn items() -> Vec<String> {
+        vec![
+            "tests/test_payment.py::test_refund".to_string(),
+            "tests/test_user.py::test_login".to_string(),
+            "tests/test_payment.py::test_charge".to_string(),
+        ]
+    }
+
+    fn matched_items(finder: &Finder) -> Vec<&str> {
+        finder
+            .matches()
+            .iter()
+            .map(|found| finder.items()[found.index].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn fuzzy_match_all_terms() {
+        let mut finder = Finder::new(items(), Some("paymnt"), true);
+        assert_eq!(
+            matched_items(&finder),
+            vec![
+                "tests/test_payment.py::test_refund",
+                "tests/test_payment.py::test_charge"
+            ]
+        );
+        finder.push_char(' ');
+        finder.push_char('c');
+        finder.push_char('h');
+        assert_eq!(
+            matched_items(&finder),
+            vec!["tests/test_payment.py::test_charge"]
+        );
+        finder.clear_query();
+        assert_eq!(matched_items(&finder), items());
+    }
+
+    #[test]
+    fn multi_select() {
+        let mut finder = Finder::new(items(), None, true);
+        assert_eq!(finder.accept(), vec![0]);
+        finder.move_down(5);
+        assert_eq!(finder.current(), Some(2));
+        finder.toggle();
+        finder.move_up(2);
+        finder.toggle();
+        assert_eq!(finder.accept(), vec![0, 2]);
+        finder.toggle_all();
+        assert_eq!(finder.accept(), vec![1]);
+        finder.deselect_all();
+        finder.select_all();
+        assert_eq!(finder.selected_count(), 3);
+
+        let mut single = Finder::new(items(), None, false);
+        single.select_all();
+        single.toggle();
+        assert_eq!(single.accept(), vec![0]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    cache::types::TestRecord, errors::FztError, runner::config::Preview, tests::TestLines,
+};
+
+use super::{Append, SearchEngine, TestSelection};
+
+mod finder;
+pub(super) mod ui;
+
+use finder::Finder;
+use ui::{Outcome, PreviewSource, run_finder};
+
+const APPEND_OPTIONS: [&str; 5] = ["Done", "Directory", "File", "Runtime", "Test"];
+
+/// Fuzzy finder running in the terminal, does not need fzf to be installed
+#[derive(Default, Clone, Debug)]
+pub struct BuiltinSearchEngine {}
+
+impl BuiltinSearchEngine {
+    fn select_one(&self, items: &[&str], preview: PreviewSource) -> Result<String, FztError> {
+        let mut finder = Finder::new(
+            items.iter().map(|item| item.to_string()).collect(),
+            None,
+            false,
+        );
+        Ok(run_finder(&mut finder, preview, &[], false)?
+            .accepted()
+            .first()
+            .copied()
+            .map(|index| items[index].to_string())
+            .unwrap_or_default())
+    }
+}
+
+impl SearchEngine for BuiltinSearchEngine {
+    fn get_tests_to_run(
+        &self,
+        all_test: &[&str],
+        preview: &Option<Preview>,
+        query: &Option<String>,
+        records: &BTreeMap<String, TestRecord>,
+        lines: &HashMap<String, TestLines>,
+    ) -> Result<TestSelection, FztError> {
+        let mut finder = Finder::new(
+            all_test.iter().map(|test| test.to_string()).collect(),
+            query.as_deref(),
+            true,
+        );
+        let lines: Vec<Option<TestLines>> = all_test
+            .iter()
+            .map(|test| lines.get(*test).copied())
+            .collect();
+        let preview = match preview {
+            Some(preview) => PreviewSource::Item(preview, lines.as_slice()),
+            None => PreviewSource::None,
+        };
+        let records: Vec<Option<&TestRecord>> =
+            all_test.iter().map(|test| records.get(*test)).collect();
+        let outcome = run_finder(&mut finder, preview, records.as_slice(), true)?;
+        if let Outcome::Switched(granularity) = outcome {
+            return Ok(TestSelection::Switch(
+                granularity,
+                finder.query().to_string(),
+            ));
+        }
+        Ok(TestSelection::Items(
+            outcome
+                .accepted()
+                .into_iter()
+                .map(|index| all_test[index].to_string())
+                .collect(),
+        ))
+    }
+
+    fn get_from_history(
+        &self,
+        history: &[Vec<String>],
+        query: &Option<String>,
+    ) -> Result<Vec<String>, FztError> {
+        let history: Vec<&Vec<String>> = history.iter().filter(|tests| !tests.is_empty()).collect();
+        // A history entry is shown on one line
+        let mut finder = Finder::new(
+            history.iter().map(|tests| tests.join(" | ")).collect(),
+            query.as_deref(),
+            true,
+        );
+        Ok(run_finder(&mut finder, PreviewSource::None, &[], false)?
+            .accepted()
+            .into_iter()
+            .flat_map(|index| history[index].clone())
+            .collect())
+    }
+
+    fn name(&self) -> String {
+        String::from("builtin")
+    }
+
+    fn appened(&self, preview: &str) -> Result<Append, FztError> {
+        let mode = self.select_one(&APPEND_OPTIONS, PreviewSource::Text(preview))?;
+        // Aborting the finder finishes the selection
+        Ok(mode.parse().unwrap_or(Append::Done))
+    }
+
+    fn select(&self, selected_items: &[&str]) -> Result<String, FztError> {
+        self.select_one(selected_items, PreviewSource::None)
+    }
+}
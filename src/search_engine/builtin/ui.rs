@@ -0,0 +1,313 @@
+use std::{
+    collections::HashMap,
+    io::{Stderr, stderr},
+};
+
+use ansi_to_tui::IntoText;
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    cache::types::TestRecord,
+    errors::FztError,
+    runner::config::Preview,
+    runtime::TestStatus,
+    tests::{TestLines, test_provider::SelectGranularity},
+};
+
+use super::{
+    super::{
+        flaky_label, granularity_for_key, preview::preview_item, record_details, status_label,
+    },
+    finder::Finder,
+};
+
+pub enum PreviewSource<'a> {
+    None,
+    // Preview of the item under the cursor, with the lines of the tests by item index
+    Item(&'a Preview, &'a [Option<TestLines>]),
+    // Same text for every item
+    Text(&'a str),
+}
+
+enum Action {
+    Continue,
+    Accept,
+    Abort,
+    Switch(SelectGranularity),
+}
+
+pub enum Outcome {
+    Accepted(Vec<usize>),
+    Aborted,
+    // One of the granularity keys was pressed
+    Switched(SelectGranularity),
+}
+
+impl Outcome {
+    /// Indices of the accepted items, empty if the finder was not accepted
+    pub fn accepted(self) -> Vec<usize> {
+        match self {
+            Outcome::Accepted(accepted) => accepted,
+            Outcome::Aborted | Outcome::Switched(_) => vec![],
+        }
+    }
+}
+
+// The finder is drawn on stderr, so stdout stays clean for the selection
+struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stderr>>,
+}
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, FztError> {
+        enable_raw_mode()?;
+        execute!(stderr(), EnterAlternateScreen)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stderr()))?;
+        Ok(Self { terminal })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(stderr(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Last result of a test, shown after the item
+pub fn record_spans<'a>(record: &TestRecord) -> Vec<Span<'a>> {
+    let color = match record.status {
+        TestStatus::Passed => Color::Green,
+        TestStatus::Failed => Color::Red,
+        TestStatus::Skipped => Color::Yellow,
+    };
+    let mut spans = vec![
+        Span::raw("  "),
+        Span::styled(status_label(&record.status), Style::default().fg(color)),
+        Span::raw(" "),
+        Span::styled(
+            record_details(record),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ];
+    if let Some(flaky) = flaky_label(record) {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(flaky, Style::default().fg(Color::Yellow)));
+    }
+    spans
+}
+
+fn item_line<'a>(
+    finder: &Finder,
+    position: usize,
+    records: &[Option<&TestRecord>],
+) -> ListItem<'a> {
+    let found = &finder.matches()[position];
+    let item = &finder.items()[found.index];
+    let cursor = if position == finder.cursor() {
+        "▌"
+    } else {
+        " "
+    };
+    let marker = if finder.is_selected(found.index) {
+        "+"
+    } else {
+        " "
+    };
+    let mut spans = vec![
+        Span::styled(cursor, Style::default().fg(Color::Red)),
+        Span::styled(format!("{} ", marker), Style::default().fg(Color::Magenta)),
+    ];
+    let highlight = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    spans.extend(item.chars().enumerate().map(|(index, ch)| {
+        if found.positions.binary_search(&index).is_ok() {
+            Span::styled(ch.to_string(), highlight)
+        } else {
+            Span::raw(ch.to_string())
+        }
+    }));
+    if let Some(Some(record)) = records.get(found.index) {
+        spans.extend(record_spans(record));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+fn draw(
+    frame: &mut Frame,
+    finder: &Finder,
+    list_state: &mut ListState,
+    preview: Option<&str>,
+    records: &[Option<&TestRecord>],
+) -> Rect {
+    let area = frame.area();
+    let finder_area = match preview {
+        Some(preview) => {
+            let [finder_area, preview_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(area);
+            // Previews of source code are highlighted with ANSI escapes
+            let preview = preview
+                .into_text()
+                .unwrap_or_else(|_| Text::raw(preview.to_string()));
+            frame.render_widget(
+                Paragraph::new(preview).block(Block::bordered()),
+                preview_area,
+            );
+            finder_area
+        }
+        None => area,
+    };
+    let [prompt_area, info_area, list_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(0),
+    ])
+    .areas(finder_area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Blue)),
+            Span::raw(finder.query()),
+        ])),
+        prompt_area,
+    );
+    frame.set_cursor_position((
+        prompt_area.x + 2 + finder.query().chars().count() as u16,
+        prompt_area.y,
+    ));
+
+    let mut info = format!("  {}/{}", finder.matches().len(), finder.items().len());
+    if finder.is_multi() {
+        info.push_str(format!(" ({})", finder.selected_count()).as_str());
+    }
+    frame.render_widget(
+        Paragraph::new(info).style(Style::default().fg(Color::DarkGray)),
+        info_area,
+    );
+
+    let items: Vec<ListItem> = (0..finder.matches().len())
+        .map(|position| item_line(finder, position, records))
+        .collect();
+    list_state.select(Some(finder.cursor()));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().bg(Color::DarkGray)),
+        list_area,
+        list_state,
+    );
+    list_area
+}
+
+// Key bindings follow fzf
+fn handle_key(
+    finder: &mut Finder,
+    key: KeyEvent,
+    page_size: usize,
+    switch_granularity: bool,
+) -> Action {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c' | 'g' | 'q') => return Action::Abort,
+            KeyCode::Char('j' | 'n') => finder.move_down(1),
+            KeyCode::Char('k' | 'p') => finder.move_up(1),
+            KeyCode::Char('u') => finder.clear_query(),
+            KeyCode::Char('a') => finder.select_all(),
+            KeyCode::Char(ch) if switch_granularity => {
+                if let Some(granularity) = granularity_for_key(format!("ctrl-{ch}").as_str()) {
+                    return Action::Switch(granularity);
+                }
+            }
+            _ => {}
+        }
+        return Action::Continue;
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        match key.code {
+            KeyCode::Char('d') => finder.deselect_all(),
+            KeyCode::Char('t') => finder.toggle_all(),
+            _ => {}
+        }
+        return Action::Continue;
+    }
+    match key.code {
+        KeyCode::Esc => return Action::Abort,
+        KeyCode::Enter => return Action::Accept,
+        KeyCode::Down => finder.move_down(1),
+        KeyCode::Up => finder.move_up(1),
+        KeyCode::PageDown => finder.move_down(page_size),
+        KeyCode::PageUp => finder.move_up(page_size),
+        KeyCode::Tab => {
+            finder.toggle();
+            finder.move_down(1);
+        }
+        KeyCode::BackTab => {
+            finder.toggle();
+            finder.move_up(1);
+        }
+        KeyCode::Backspace => finder.pop_char(),
+        KeyCode::Char(ch) => finder.push_char(ch),
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Run the finder until the user accepts, aborts or switches the granularity.
+/// `records` holds the last result of the item with the same index, if any.
+pub fn run_finder(
+    finder: &mut Finder,
+    preview: PreviewSource,
+    records: &[Option<&TestRecord>],
+    switch_granularity: bool,
+) -> Result<Outcome, FztError> {
+    let mut guard = TerminalGuard::enter()?;
+    let mut list_state = ListState::default();
+    let mut previews: HashMap<usize, String> = HashMap::new();
+    let mut page_size = 1;
+    loop {
+        let preview_text = match preview {
+            PreviewSource::None => None,
+            PreviewSource::Text(text) => Some(text),
+            PreviewSource::Item(preview, lines) => finder.current().map(|index| {
+                previews
+                    .entry(index)
+                    .or_insert_with(|| {
+                        preview_item(
+                            finder.items()[index].as_str(),
+                            preview,
+                            records.get(index).copied().flatten(),
+                            lines.get(index).copied().flatten(),
+                            true,
+                        )
+                    })
+                    .as_str()
+            }),
+        };
+        guard.terminal.draw(|frame| {
+            page_size = draw(frame, finder, &mut list_state, preview_text, records).height as usize;
+        })?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match handle_key(finder, key, page_size.max(1), switch_granularity) {
+                Action::Continue => {}
+                Action::Accept => return Ok(Outcome::Accepted(finder.accept())),
+                Action::Abort => return Ok(Outcome::Aborted),
+                Action::Switch(granularity) => return Ok(Outcome::Switched(granularity)),
+            }
+        }
+    }
+}
@@ -1,27 +1,90 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Output, Stdio};
 use std::str::{self, FromStr};
 
+use tempfile::NamedTempFile;
+
+use crate::cache::types::TestRecord;
 use crate::errors::FztError;
 use crate::runner::config::Preview;
+use crate::runtime::TestStatus;
+use crate::runtime::dry_run::quote;
+use crate::tests::TestLines;
 
 use super::Append;
 use super::SearchEngine;
+use super::{
+    GRANULARITY_KEYS, SELECT_BINDINGS, TestSelection, flaky_label, granularity_for_key,
+    record_details, status_label,
+};
+
+// Hidden subcommand rendering the preview of an item
+const PREVIEW_COMMAND: &str = "__preview";
+// Separates the test from its last result, the result field is excluded from matching
+const RECORD_SEPARATOR: char = '\t';
+
+// Test followed by its colored last result
+fn annotate(test: &str, record: Option<&TestRecord>) -> String {
+    let Some(record) = record else {
+        return format!("{test}{RECORD_SEPARATOR}");
+    };
+    let color = match record.status {
+        TestStatus::Passed => 32,
+        TestStatus::Failed => 31,
+        TestStatus::Skipped => 33,
+    };
+    let flaky = flaky_label(record)
+        .map(|flaky| format!(" \x1b[33m{flaky}\x1b[0m"))
+        .unwrap_or_default();
+    format!(
+        "{test}{RECORD_SEPARATOR}\x1b[{color}m{}\x1b[0m \x1b[2m{}\x1b[0m{flaky}",
+        status_label(&record.status),
+        record_details(record)
+    )
+}
 
-const BAT_PREVIEW_SCRIPT: &str = include_str!("bat_preview_command.sh");
-const BAT_LINE_PREVIEW_CONTEXT: i8 = 20;
+// The executable is quoted, fzf runs the preview in a shell
+fn preview_command(exe: &Path, mode: &str) -> String {
+    format!(
+        "{} {PREVIEW_COMMAND} {mode} {{}}",
+        quote(exe.to_string_lossy().as_ref())
+    )
+}
+
+// All previews of a finder session in one file
+fn write_preview_index(index: &BTreeMap<usize, String>) -> Result<NamedTempFile, FztError> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(serde_json::to_string(index)?.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// Preview of the line `index` written to the index file, used by the `__preview` command
+pub fn read_preview_index(index_file: &Path, index: usize) -> Option<String> {
+    let content = fs::read_to_string(index_file).ok()?;
+    serde_json::from_str::<BTreeMap<usize, String>>(content.as_str())
+        .ok()?
+        .remove(&index)
+}
 
 fn run_fzf(
     input: &str,
     read_null: bool,
     preview: &Option<Preview>,
     query: &Option<String>,
+    annotated: bool,
+    index_file: Option<&Path>,
+    switch_granularity: bool,
 ) -> Result<Output, FztError> {
     let mut command = Command::new("fzf");
     command
         .arg("-m")
         .arg("--bind")
-        .arg("ctrl-a:select-all,ctrl-d:deselect-all,ctrl-t:toggle-all")
+        .arg(SELECT_BINDINGS)
         .arg("--height")
         .arg("50%")
         .stdin(Stdio::piped())
@@ -35,33 +98,46 @@ fn run_fzf(
         command.arg("--query").arg(query);
     }
 
+    // The query and the pressed key are printed before the selection
+    if switch_granularity {
+        command.arg("--print-query").arg("--expect").arg(
+            GRANULARITY_KEYS
+                .iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<&str>>()
+                .join(","),
+        );
+    }
+
+    if annotated {
+        // The last field holds the result of the test
+        command
+            .arg("--ansi")
+            .arg("--delimiter")
+            .arg(format!("::|{RECORD_SEPARATOR}"))
+            .arg("--nth")
+            .arg("..-2");
+    }
+
     if let Some(preview_mode) = preview {
-        match preview_mode {
-            Preview::File => {
-                command
-                    .arg("--delimiter")
-                    .arg("::")
-                    .arg("--preview")
-                    .arg("bat --style=numbers --color=always {1}");
-            }
-            Preview::Test => {
-                command
-                    .arg("--delimiter")
-                    .arg("::")
-                    .arg("--preview")
-                    .arg(format!(
-                        "bash -c '{}' -- {{2}} {{1}} {BAT_LINE_PREVIEW_CONTEXT}",
-                        BAT_PREVIEW_SCRIPT.replace('\'', "'\"'\"'")
-                    ));
-            }
-            Preview::Directory => {
-                command
-                    .arg("--delimiter")
-                    .arg("::")
-                    .arg("--preview")
-                    .arg(" ls '{1}'");
-            }
+        let exe = env::current_exe()?;
+        let mut preview = match preview_mode {
+            Preview::File => preview_command(&exe, "file"),
+            Preview::Test => preview_command(&exe, "test"),
+            Preview::Directory => preview_command(&exe, "directory"),
+            Preview::Failure => preview_command(&exe, "failure"),
+        };
+        // The preview of the n-th line is looked up in the index
+        if let Some(index_file) = index_file {
+            preview.push_str(
+                format!(
+                    " --index-file {} --index {{n}}",
+                    quote(&index_file.to_string_lossy())
+                )
+                .as_str(),
+            );
         }
+        command.arg("--preview").arg(preview);
     }
 
     let mut child = command.spawn()?;
@@ -104,17 +180,59 @@ impl SearchEngine for FzfSearchEngine {
         all_test: &[&str],
         preview: &Option<Preview>,
         query: &Option<String>,
-    ) -> Result<Vec<String>, FztError> {
+        records: &BTreeMap<String, TestRecord>,
+        lines: &HashMap<String, TestLines>,
+    ) -> Result<TestSelection, FztError> {
+        let annotated = !records.is_empty();
         let mut input = String::new();
         all_test.iter().for_each(|test| {
-            input.push_str(format!("{}\n", test).as_str());
+            if annotated {
+                input.push_str(format!("{}\n", annotate(test, records.get(*test))).as_str());
+            } else {
+                input.push_str(format!("{}\n", test).as_str());
+            }
         });
-        let output = run_fzf(input.as_str(), false, preview, query)?;
-        let tests: Vec<String> = str::from_utf8(output.stdout.as_slice())?
-            .lines()
+        // Failure output or lines of the test by line index, shown by the preview
+        let index: BTreeMap<usize, String> = match preview {
+            Some(Preview::Failure) => all_test
+                .iter()
+                .enumerate()
+                .filter_map(|(index, test)| {
+                    let record = records.get(*test)?;
+                    Some((index, record.failure.clone()?))
+                })
+                .collect(),
+            Some(Preview::Test) => all_test
+                .iter()
+                .enumerate()
+                .filter_map(|(index, test)| Some((index, lines.get(*test)?.to_string())))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        let index_file = if index.is_empty() {
+            None
+        } else {
+            Some(write_preview_index(&index)?)
+        };
+        let output = run_fzf(
+            input.as_str(),
+            false,
+            preview,
+            query,
+            annotated,
+            index_file.as_ref().map(|file| file.path()),
+            true,
+        )?;
+        let mut lines = str::from_utf8(output.stdout.as_slice())?.lines();
+        let query = lines.next().unwrap_or_default().to_string();
+        if let Some(granularity) = lines.next().and_then(granularity_for_key) {
+            return Ok(TestSelection::Switch(granularity, query));
+        }
+        let tests: Vec<String> = lines
+            .filter_map(|line| line.split(RECORD_SEPARATOR).next())
             .map(|line| line.to_string())
             .collect();
-        Ok(tests)
+        Ok(TestSelection::Items(tests))
     }
 
     fn get_from_history(
@@ -134,7 +252,7 @@ impl SearchEngine for FzfSearchEngine {
                 command.remove(command.len() - 1);
                 input.push_str(format!("{command}\0").as_str());
             });
-        let mut output = run_fzf(input.as_str(), true, &None, query)?.stdout;
+        let mut output = run_fzf(input.as_str(), true, &None, query, false, None, false)?.stdout;
         // Replace Null byte with new line
         output.iter_mut().filter(|p| **p == 0).for_each(|p| *p = 10);
         Ok(str::from_utf8(output.as_slice())?
@@ -159,3 +277,32 @@ impl SearchEngine for FzfSearchEngine {
         Ok(str::from_utf8(output.stdout.as_slice())?.trim().to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn quote_preview_command() {
+        assert_eq!(
+            preview_command(Path::new("/usr/bin/fzt"), "test"),
+            "/usr/bin/fzt __preview test {}"
+        );
+        assert_eq!(
+            preview_command(Path::new("/home/o'neil/my bin/fzt"), "file"),
+            "'/home/o'\\''neil/my bin/fzt' __preview file {}"
+        );
+    }
+
+    #[test]
+    fn preview_index() {
+        let index = BTreeMap::from([(0, "3:4".to_string()), (2, "assert 1 == 2".to_string())]);
+        let file = write_preview_index(&index).unwrap();
+        assert_eq!(
+            read_preview_index(file.path(), 2),
+            Some("assert 1 == 2".to_string())
+        );
+        assert_eq!(read_preview_index(file.path(), 1), None);
+    }
+}
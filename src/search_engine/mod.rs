@@ -1,8 +1,24 @@
-use std::str::FromStr;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
-use crate::{errors::FztError, runner::config::Preview};
+use crate::{
+    cache::types::TestRecord,
+    errors::FztError,
+    runner::config::Preview,
+    runtime::TestStatus,
+    tests::{TestLines, test_provider::SelectGranularity},
+};
 
+use self::skim::SkimSearchEngine;
+use builtin::BuiltinSearchEngine;
+use fzf::FzfSearchEngine;
+
+pub mod builtin;
 pub mod fzf;
+pub mod preview;
+pub mod skim;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Append {
@@ -28,13 +44,78 @@ impl FromStr for Append {
     }
 }
 
+/// Keys selecting all, deselecting all and toggling all items
+pub const SELECT_BINDINGS: &str = "ctrl-a:select-all,alt-d:deselect-all,alt-t:toggle-all";
+
+/// Keys switching the granularity of the test finder, the query is kept
+pub const GRANULARITY_KEYS: [(&str, SelectGranularity); 4] = [
+    ("ctrl-t", SelectGranularity::Test),
+    ("ctrl-f", SelectGranularity::File),
+    ("ctrl-d", SelectGranularity::Directory),
+    ("ctrl-r", SelectGranularity::RunTime),
+];
+
+pub fn granularity_for_key(key: &str) -> Option<SelectGranularity> {
+    GRANULARITY_KEYS
+        .iter()
+        .find(|(granularity_key, _)| *granularity_key == key)
+        .map(|(_, granularity)| *granularity)
+}
+
+/// Result of the test finder
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestSelection {
+    Items(Vec<String>),
+    // The finder has to be reopened with items of the granularity and the query
+    Switch(SelectGranularity, String),
+}
+
+/// Status shown next to a test in the finder
+pub fn status_label(status: &TestStatus) -> &'static str {
+    match status {
+        TestStatus::Passed => "pass",
+        TestStatus::Failed => "fail",
+        TestStatus::Skipped => "skip",
+    }
+}
+
+/// Duration and failure count shown after the status, e.g. `1.30s failed 3x`
+pub fn record_details(record: &TestRecord) -> String {
+    let mut details = vec![];
+    if let Some(duration) = record.duration {
+        if duration < 1.0 {
+            details.push(format!("{}ms", (duration * 1000.0).round()));
+        } else {
+            details.push(format!("{:.2}s", duration));
+        }
+    }
+    if record.failures > 0 {
+        details.push(format!("failed {}x", record.failures));
+    }
+    details.join(" ")
+}
+
+/// Highlighted after the details if the test passed on a retry before, e.g. `flaky 2x`
+pub fn flaky_label(record: &TestRecord) -> Option<String> {
+    if record.flaky > 0 {
+        Some(format!("flaky {}x", record.flaky))
+    } else {
+        None
+    }
+}
+
 pub trait SearchEngine {
+    /// `records` holds the last result of tests by item name, it is shown next to the items
+    /// but not used for matching. `lines` locates the tests by item name for the test preview.
+    /// Pressing one of the `GRANULARITY_KEYS` returns a switch.
     fn get_tests_to_run(
         &self,
         all_test: &[&str],
         preview: &Option<Preview>,
         query: &Option<String>,
-    ) -> Result<Vec<String>, FztError>;
+        records: &BTreeMap<String, TestRecord>,
+        lines: &HashMap<String, TestLines>,
+    ) -> Result<TestSelection, FztError>;
     fn get_from_history(
         &self,
         history: &[Vec<String>],
@@ -44,3 +125,98 @@ pub trait SearchEngine {
     fn appened(&self, selected_items: &str) -> Result<Append, FztError>;
     fn select(&self, selected_items: &[&str]) -> Result<String, FztError>;
 }
+
+/// Search engine selected with `--search-engine`
+#[derive(Clone, Debug)]
+pub enum ConfiguredSearchEngine {
+    Fzf(FzfSearchEngine),
+    Builtin(BuiltinSearchEngine),
+    Skim(SkimSearchEngine),
+}
+
+impl Default for ConfiguredSearchEngine {
+    fn default() -> Self {
+        ConfiguredSearchEngine::Fzf(FzfSearchEngine::default())
+    }
+}
+
+impl ConfiguredSearchEngine {
+    fn engine(&self) -> &dyn SearchEngine {
+        match self {
+            ConfiguredSearchEngine::Fzf(engine) => engine,
+            ConfiguredSearchEngine::Builtin(engine) => engine,
+            ConfiguredSearchEngine::Skim(engine) => engine,
+        }
+    }
+}
+
+impl SearchEngine for ConfiguredSearchEngine {
+    fn get_tests_to_run(
+        &self,
+        all_test: &[&str],
+        preview: &Option<Preview>,
+        query: &Option<String>,
+        records: &BTreeMap<String, TestRecord>,
+        lines: &HashMap<String, TestLines>,
+    ) -> Result<TestSelection, FztError> {
+        self.engine()
+            .get_tests_to_run(all_test, preview, query, records, lines)
+    }
+
+    fn get_from_history(
+        &self,
+        history: &[Vec<String>],
+        query: &Option<String>,
+    ) -> Result<Vec<String>, FztError> {
+        self.engine().get_from_history(history, query)
+    }
+
+    fn name(&self) -> String {
+        self.engine().name()
+    }
+
+    fn appened(&self, selected_items: &str) -> Result<Append, FztError> {
+        self.engine().appened(selected_items)
+    }
+
+    fn select(&self, selected_items: &[&str]) -> Result<String, FztError> {
+        self.engine().select(selected_items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_record_details() {
+        let mut record = TestRecord {
+            status: TestStatus::Failed,
+            duration: Some(1.3),
+            failures: 3,
+            failure: None,
+            flaky: 0,
+        };
+        assert_eq!(record_details(&record), "1.30s failed 3x");
+        record.duration = Some(0.0124);
+        record.failures = 0;
+        assert_eq!(record_details(&record), "12ms");
+        record.duration = None;
+        assert_eq!(record_details(&record), "");
+        assert_eq!(flaky_label(&record), None);
+        record.flaky = 2;
+        assert_eq!(flaky_label(&record), Some("flaky 2x".to_string()));
+    }
+
+    #[test]
+    fn switch_granularity_keys() {
+        assert_eq!(granularity_for_key("ctrl-f"), Some(SelectGranularity::File));
+        assert_eq!(
+            granularity_for_key("ctrl-d"),
+            Some(SelectGranularity::Directory)
+        );
+        assert_eq!(granularity_for_key("ctrl-a"), None);
+        assert_eq!(granularity_for_key(""), None);
+    }
+}
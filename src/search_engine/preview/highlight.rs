@@ -0,0 +1,50 @@
+use std::{path::Path, sync::OnceLock};
+
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    parsing::SyntaxSet,
+    util::as_24_bit_terminal_escaped,
+};
+
+const THEME: &str = "base16-ocean.dark";
+const RESET: &str = "\x1b[0m";
+
+// Loading the syntaxes takes a while, the finders highlight many previews
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    &THEME_SET.get_or_init(ThemeSet::load_defaults).themes[THEME]
+}
+
+/// Highlights the lines of the file as ANSI escaped text.
+/// Lines are returned unchanged if the language of the file is unknown.
+pub fn highlight(path: &str, lines: &[&str]) -> Vec<String> {
+    let syntax = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| syntaxes().find_syntax_by_extension(extension));
+    let Some(syntax) = syntax else {
+        return lines.iter().map(|line| line.to_string()).collect();
+    };
+    let mut highlighter = HighlightLines::new(syntax, theme());
+    lines
+        .iter()
+        .map(|line| {
+            // The syntaxes expect lines with newline
+            let line = format!("{}\n", line);
+            match highlighter.highlight_line(line.as_str(), syntaxes()) {
+                Ok(ranges) => format!(
+                    "{}{}",
+                    as_24_bit_terminal_escaped(&ranges, false).trim_end_matches('\n'),
+                    RESET
+                ),
+                Err(_) => line.trim_end_matches('\n').to_string(),
+            }
+        })
+        .collect()
+}
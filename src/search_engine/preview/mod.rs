@@ -0,0 +1,190 @@
+use std::{fs, path::Path};
+
+use crate::{cache::types::TestRecord, runner::config::Preview, tests::TestLines};
+
+use highlight::highlight;
+
+mod highlight;
+
+// Context shown if the test can not be located in the file
+const TEST_PREVIEW_CONTEXT: usize = 20;
+const FILE_PREVIEW_LINES: usize = 500;
+const SEPARATOR: &str = "--------------------------------------------";
+pub const NO_FAILURE_OUTPUT: &str = "No failure output recorded";
+
+fn numbered(path: &str, lines: &[&str], first_line: usize, color: bool) -> String {
+    let lines: Vec<String> = if color {
+        highlight(path, lines)
+    } else {
+        lines.iter().map(|line| line.to_string()).collect()
+    };
+    lines
+        .iter()
+        .enumerate()
+        .map(|(offset, line)| format!("{:>4} │ {}", first_line + offset, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn file_preview(path: &str, color: bool) -> String {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let lines: Vec<&str> = content.lines().take(FILE_PREVIEW_LINES).collect();
+            numbered(path, lines.as_slice(), 1, color)
+        }
+        Err(error) => format!("Can not read {}: {}", path, error),
+    }
+}
+
+// Lines containing the test name, used if the test was not located on discovery
+fn matching_lines(lines: &[&str], test: &str) -> Vec<TestLines> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.contains(test))
+        .map(|(index, _)| TestLines {
+            start: index + 1,
+            end: (index + TEST_PREVIEW_CONTEXT).min(lines.len()),
+        })
+        .collect()
+}
+
+fn test_preview(path: &str, test: &str, test_lines: Option<TestLines>, color: bool) -> String {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => return format!("Can not read {}: {}", path, error),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let test_lines = match test_lines {
+        Some(test_lines) => vec![test_lines],
+        None => matching_lines(lines.as_slice(), test),
+    };
+    let sections: Vec<String> = test_lines
+        .iter()
+        .filter(|test_lines| test_lines.start <= test_lines.end && test_lines.end <= lines.len())
+        .map(|test_lines| {
+            numbered(
+                path,
+                &lines[test_lines.start - 1..test_lines.end],
+                test_lines.start,
+                color,
+            )
+        })
+        .collect();
+    if sections.is_empty() {
+        "No matches found".to_string()
+    } else {
+        sections.join(format!("\n\n{}\n\n", SEPARATOR).as_str())
+    }
+}
+
+fn directory_preview(path: &str) -> String {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return format!("Can not read {}: {}", path, error),
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        })
+        .collect();
+    names.sort();
+    names.join("\n")
+}
+
+/// Preview of a selection item, items have the format `<FILE_PATH>::<TEST_NAME>`.
+/// `record` is the last result of the test, used by the failure preview.
+/// `lines` locates the test in its file, tests without lines are searched by name.
+/// Source code is highlighted with ANSI escapes if `color` is set.
+pub fn preview_item(
+    item: &str,
+    preview: &Preview,
+    record: Option<&TestRecord>,
+    lines: Option<TestLines>,
+    color: bool,
+) -> String {
+    let mut parts = item.split("::");
+    let path = parts.next().unwrap_or_default();
+    match preview {
+        Preview::File => file_preview(path, color),
+        Preview::Test => match item.rsplit("::").next() {
+            Some(test) if test != path => test_preview(path, test, lines, color),
+            _ => file_preview(path, color),
+        },
+        Preview::Directory => {
+            if Path::new(path).is_dir() {
+                directory_preview(path)
+            } else {
+                directory_preview(".")
+            }
+        }
+        Preview::Failure => record
+            .and_then(|record| record.failure.clone())
+            .unwrap_or_else(|| NO_FAILURE_OUTPUT.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn preview_test_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("test_pay.py");
+        fs::write(
+            &file,
+            "import pay\n\ndef test_refund():\n    assert pay.refund()\n\n\ndef test_charge():\n    test_refund()\n",
+        )
+        .unwrap();
+        let item = format!("{}::test_refund", file.display());
+        assert_eq!(
+            preview_item(
+                item.as_str(),
+                &Preview::Test,
+                None,
+                Some(TestLines { start: 3, end: 4 }),
+                false
+            ),
+            "   3 │ def test_refund():\n   4 │     assert pay.refund()"
+        );
+        // Tests without lines are searched by name
+        assert_eq!(
+            preview_item(
+                format!("{}::test_charge", file.display()).as_str(),
+                &Preview::Test,
+                None,
+                None,
+                false
+            ),
+            "   7 │ def test_charge():\n   8 │     test_refund()"
+        );
+        assert_eq!(
+            preview_item(
+                format!("{}::test_missing", file.display()).as_str(),
+                &Preview::Test,
+                None,
+                None,
+                false
+            ),
+            "No matches found"
+        );
+        assert_eq!(
+            preview_item(
+                dir.path().to_str().unwrap(),
+                &Preview::Directory,
+                None,
+                None,
+                false
+            ),
+            "test_pay.py"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use ::skim::{
+    DisplayContext, ItemPreview, PreviewContext, Skim, SkimItem,
+    prelude::{Action, Event, SkimOptions, SkimOptionsBuilder, SkimOutput},
+};
+use ratatui::text::Line;
+
+use crate::{
+    cache::types::TestRecord, errors::FztError, runner::config::Preview, tests::TestLines,
+};
+
+use super::{
+    Append, GRANULARITY_KEYS, SELECT_BINDINGS, SearchEngine, TestSelection,
+    builtin::ui::record_spans, granularity_for_key, preview::preview_item,
+};
+
+const APPEND_OPTIONS: [&str; 5] = ["Done", "Directory", "File", "Runtime", "Test"];
+const HEIGHT: &str = "50%";
+
+enum EntryPreview {
+    None,
+    // Preview of the item under the cursor
+    Item(Preview),
+    // Same text for every item
+    Text(Arc<str>),
+}
+
+struct Entry {
+    text: String,
+    preview: EntryPreview,
+    // Last result of the test, shown after the text but not matched
+    record: Option<TestRecord>,
+    lines: Option<TestLines>,
+}
+
+impl SkimItem for Entry {
+    fn text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.text.as_str())
+    }
+
+    fn display(&self, context: DisplayContext) -> Line<'_> {
+        let mut line = context.to_line(self.text());
+        if let Some(record) = &self.record {
+            line.spans.extend(record_spans(record));
+        }
+        line
+    }
+
+    fn preview(&self, _context: PreviewContext) -> ItemPreview {
+        match &self.preview {
+            EntryPreview::None => ItemPreview::Text(String::new()),
+            EntryPreview::Item(preview) => ItemPreview::AnsiText(preview_item(
+                &self.text,
+                preview,
+                self.record.as_ref(),
+                self.lines,
+                true,
+            )),
+            EntryPreview::Text(text) => ItemPreview::Text(text.to_string()),
+        }
+    }
+}
+
+fn options(
+    multi: bool,
+    query: &Option<String>,
+    with_preview: bool,
+    multiline: bool,
+    switch_granularity: bool,
+) -> Result<SkimOptions, FztError> {
+    let mut builder = SkimOptionsBuilder::default();
+    builder.multi(multi).height(HEIGHT.to_string());
+    // Same bindings as fzf
+    let mut bindings = vec![];
+    if multi {
+        bindings.push(SELECT_BINDINGS.to_string());
+    }
+    // Accepting with the key makes it the final event
+    if switch_granularity {
+        bindings.extend(
+            GRANULARITY_KEYS
+                .iter()
+                .map(|(key, _)| format!("{key}:accept({key})")),
+        );
+    }
+    builder.bind(bindings);
+    if let Some(query) = query {
+        builder.query(query.clone());
+    }
+    // Entries render their own preview, the preview command is never run
+    if with_preview {
+        builder.preview(String::new());
+    }
+    // History entries are shown with one test per line, like `fzf --read0 --gap`
+    if multiline {
+        builder.multiline(Some(String::from("\n")));
+    }
+    builder
+        .build()
+        .map_err(|error| FztError::InternalError(error.to_string()))
+}
+
+fn skim_output(options: SkimOptions, entries: Vec<Entry>) -> Result<SkimOutput, FztError> {
+    Skim::run_items(options, entries).map_err(|error| FztError::InternalError(error.to_string()))
+}
+
+/// Returns the output of the accepted entries, empty if skim was aborted
+fn selected_output(output: &SkimOutput) -> Vec<String> {
+    if output.is_abort {
+        return vec![];
+    }
+    output
+        .selected_items
+        .iter()
+        .map(|selected| selected.item.output().to_string())
+        .collect()
+}
+
+/// Accepting with one of the `GRANULARITY_KEYS` switches the granularity
+fn test_selection(final_event: &Event, query: &str, selected: Vec<String>) -> TestSelection {
+    if let Event::Action(Action::Accept(Some(key))) = final_event
+        && let Some(granularity) = granularity_for_key(key)
+    {
+        return TestSelection::Switch(granularity, query.to_string());
+    }
+    TestSelection::Items(selected)
+}
+
+fn run_skim(options: SkimOptions, entries: Vec<Entry>) -> Result<Vec<String>, FztError> {
+    Ok(selected_output(&skim_output(options, entries)?))
+}
+
+/// Fuzzy finder using skim as a library, does not need fzf to be installed
+#[derive(Default, Clone, Debug)]
+pub struct SkimSearchEngine {}
+
+impl SkimSearchEngine {
+    fn select_one(&self, items: &[&str], preview: Option<&str>) -> Result<String, FztError> {
+        let preview: Option<Arc<str>> = preview.map(Arc::from);
+        let entries = items
+            .iter()
+            .map(|item| Entry {
+                text: item.to_string(),
+                preview: match &preview {
+                    Some(text) => EntryPreview::Text(text.clone()),
+                    None => EntryPreview::None,
+                },
+                record: None,
+                lines: None,
+            })
+            .collect();
+        let options = options(false, &None, preview.is_some(), false, false)?;
+        Ok(run_skim(options, entries)?
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
+}
+
+impl SearchEngine for SkimSearchEngine {
+    fn get_tests_to_run(
+        &self,
+        all_test: &[&str],
+        preview: &Option<Preview>,
+        query: &Option<String>,
+        records: &BTreeMap<String, TestRecord>,
+        lines: &HashMap<String, TestLines>,
+    ) -> Result<TestSelection, FztError> {
+        let entries = all_test
+            .iter()
+            .map(|test| Entry {
+                text: test.to_string(),
+                preview: match preview {
+                    Some(preview) => EntryPreview::Item(preview.clone()),
+                    None => EntryPreview::None,
+                },
+                record: records.get(*test).cloned(),
+                lines: lines.get(*test).copied(),
+            })
+            .collect();
+        let output = skim_output(
+            options(true, query, preview.is_some(), false, true)?,
+            entries,
+        )?;
+        Ok(test_selection(
+            &output.final_event,
+            &output.query,
+            selected_output(&output),
+        ))
+    }
+
+    fn get_from_history(
+        &self,
+        history: &[Vec<String>],
+        query: &Option<String>,
+    ) -> Result<Vec<String>, FztError> {
+        let entries = history
+            .iter()
+            .filter(|tests| !tests.is_empty())
+            .map(|tests| Entry {
+                text: tests.join("\n"),
+                preview: EntryPreview::None,
+                record: None,
+                lines: None,
+            })
+            .collect();
+        let options = options(true, query, false, true, false)?;
+        Ok(run_skim(options, entries)?
+            .iter()
+            .flat_map(|entry| entry.lines().map(String::from))
+            .collect())
+    }
+
+    fn name(&self) -> String {
+        String::from("skim")
+    }
+
+    fn appened(&self, preview: &str) -> Result<Append, FztError> {
+        let mode = self.select_one(&APPEND_OPTIONS, Some(preview))?;
+        // Aborting skim finishes the selection
+        Ok(mode.parse().unwrap_or(Append::Done))
+    }
+
+    fn select(&self, selected_items: &[&str]) -> Result<String, FztError> {
+        self.select_one(selected_items, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_provider::SelectGranularity;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_selection() {
+        let selected = vec!["tests/test_a.py::test_a".to_string()];
+        assert_eq!(
+            test_selection(
+                &Event::Action(Action::Accept(None)),
+                "test_a",
+                selected.clone()
+            ),
+            TestSelection::Items(selected.clone())
+        );
+        assert_eq!(
+            test_selection(
+                &Event::Action(Action::Accept(Some("ctrl-f".to_string()))),
+                "test_a",
+                selected.clone()
+            ),
+            TestSelection::Switch(SelectGranularity::File, "test_a".to_string())
+        );
+        // Keys that do not switch the granularity accept the selection
+        assert_eq!(
+            test_selection(
+                &Event::Action(Action::Accept(Some("ctrl-x".to_string()))),
+                "",
+                selected.clone()
+            ),
+            TestSelection::Items(selected)
+        );
+    }
+
+    #[test]
+    fn switch_bindings() {
+        let finder = options(true, &None, false, false, true).unwrap();
+        assert_eq!(
+            finder.bind,
+            vec![
+                SELECT_BINDINGS.to_string(),
+                "ctrl-t:accept(ctrl-t)".to_string(),
+                "ctrl-f:accept(ctrl-f)".to_string(),
+                "ctrl-d:accept(ctrl-d)".to_string(),
+                "ctrl-r:accept(ctrl-r)".to_string(),
+            ]
+        );
+        let select_one = options(false, &None, false, false, false).unwrap();
+        assert!(select_one.bind.is_empty());
+    }
+}
@@ -13,10 +13,10 @@ pub fn parse_failed_tests(
         if let Some((class_path, method_name)) = failed_test.name.rsplit_once('.') {
             // We also push methods that are actually not part of the actual test,
             // but filtering them out is done later.
-            java_tests.push(JavaTest {
-                class_path: class_path.to_string(),
-                method_name: method_name.to_string(),
-            })
+            java_tests.push(JavaTest::new(
+                class_path.to_string(),
+                method_name.to_string(),
+            ))
         }
     });
 
@@ -24,10 +24,11 @@ pub fn parse_failed_tests(
         .iter()
         .fold(HashMap::new(), |mut acc, (file_path, tests)| {
             java_tests.iter().for_each(|java_test| {
-                if tests.contains(java_test) {
+                // The current test also holds the lines of the test
+                if let Some(test) = tests.iter().find(|test| test.is_same_test(java_test)) {
                     acc.entry(file_path.clone())
                         .or_insert(vec![])
-                        .push(java_test.clone());
+                        .push(test.clone());
                 }
             });
             acc
@@ -40,7 +41,10 @@ mod tests {
 
     use crate::{
         runtime::FailedTest,
-        tests::java::{helper::parse_failed_tests, java_test::JavaTest},
+        tests::{
+            TestLines,
+            java::{helper::parse_failed_tests, java_test::JavaTest},
+        },
     };
 
     #[test]
@@ -67,14 +71,17 @@ mod tests {
                     JavaTest {
                         class_path: String::from("org.parser.ParserTest"),
                         method_name: String::from("boo"),
+                        lines: Some(TestLines { start: 3, end: 5 }),
                     },
                     JavaTest {
                         class_path: String::from("org.parser.ParserTest"),
                         method_name: String::from("foo"),
+                        lines: None,
                     },
                     JavaTest {
                         class_path: String::from("org.parser.ParserTest"),
                         method_name: String::from("hoo"),
+                        lines: None,
                     },
                 ],
             ),
@@ -83,6 +90,7 @@ mod tests {
                 vec![JavaTest {
                     class_path: String::from("org.parser.SomeParserTest"),
                     method_name: String::from("parseCache"),
+                    lines: None,
                 }],
             ),
         ]);
@@ -93,14 +101,17 @@ mod tests {
                 JavaTest {
                     class_path: String::from("org.parser.ParserTest"),
                     method_name: String::from("boo"),
+                    lines: Some(TestLines { start: 3, end: 5 }),
                 },
                 JavaTest {
                     class_path: String::from("org.parser.ParserTest"),
                     method_name: String::from("foo"),
+                    lines: None,
                 },
                 JavaTest {
                     class_path: String::from("org.parser.ParserTest"),
                     method_name: String::from("hoo"),
+                    lines: None,
                 },
             ],
         )]);
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     errors::FztError,
     runtime::FailedTest,
-    tests::{Test, Tests},
+    tests::{Test, TestLines, Tests},
 };
 
 use super::{helper::parse_failed_tests, parser::JavaParser};
@@ -33,6 +33,24 @@ impl JavaTests {
 pub struct JavaTest {
     pub class_path: String,
     pub method_name: String,
+    // Emitted by the parser, caches of older versions have no lines
+    #[serde(default)]
+    pub lines: Option<TestLines>,
+}
+
+impl JavaTest {
+    pub fn new(class_path: String, method_name: String) -> Self {
+        Self {
+            class_path,
+            method_name,
+            lines: None,
+        }
+    }
+
+    // Lines change when the file is edited, the test stays the same
+    pub fn is_same_test(&self, other: &JavaTest) -> bool {
+        self.class_path == other.class_path && self.method_name == other.method_name
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +58,7 @@ pub struct JavaTestItem {
     pub path: String,
     pub class_path: String,
     pub test: String,
+    pub lines: Option<TestLines>,
 }
 
 impl JavaTestItem {
@@ -48,8 +67,14 @@ impl JavaTestItem {
             path,
             class_path,
             test,
+            lines: None,
         }
     }
+
+    pub fn with_lines(mut self, lines: Option<TestLines>) -> Self {
+        self.lines = lines;
+        self
+    }
 }
 
 impl Test for JavaTestItem {
@@ -64,6 +89,10 @@ impl Test for JavaTestItem {
     fn file_path(&self) -> String {
         self.path.clone()
     }
+
+    fn lines(&self) -> Option<TestLines> {
+        self.lines
+    }
 }
 
 impl Tests for JavaTests {
@@ -75,11 +104,14 @@ impl Tests for JavaTests {
         let mut output = vec![];
         self.tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(JavaTestItem::new(
-                    path.clone(),
-                    test.class_path.clone(),
-                    test.method_name.clone(),
-                ));
+                output.push(
+                    JavaTestItem::new(
+                        path.clone(),
+                        test.class_path.clone(),
+                        test.method_name.clone(),
+                    )
+                    .with_lines(test.lines),
+                );
             });
         });
         output
@@ -97,7 +129,16 @@ impl Tests for JavaTests {
                     .tests
                     .get(path)
                     .expect("THIS IS A BUG. Failed tests should be a subset of tests");
-                failed_tests.retain(|test| tests.contains(test));
+                // Failed tests take over the lines of the current tests
+                *failed_tests = failed_tests
+                    .iter()
+                    .filter_map(|failed_test| {
+                        tests
+                            .iter()
+                            .find(|test| test.is_same_test(failed_test))
+                            .cloned()
+                    })
+                    .collect();
             });
         Ok(updated)
     }
@@ -116,11 +157,14 @@ impl Tests for JavaTests {
         let mut output = vec![];
         self.failed_tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(JavaTestItem::new(
-                    path.clone(),
-                    test.class_path.clone(),
-                    test.method_name.clone(),
-                ));
+                output.push(
+                    JavaTestItem::new(
+                        path.clone(),
+                        test.class_path.clone(),
+                        test.method_name.clone(),
+                    )
+                    .with_lines(test.lines),
+                );
             });
         });
         output
@@ -133,10 +177,9 @@ impl Tests for JavaTests {
         todo!()
     }
 
-    #[allow(unreachable_code)]
+    // Coverage is not supported, so no test is covered
     fn get_covered_tests(&self) -> Vec<impl Test> {
-        todo!();
-        return Vec::<JavaTestItem>::new();
+        Vec::<JavaTestItem>::new()
     }
 }
 
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
 
 use crate::{errors::FztError, runtime::FailedTest};
 
@@ -7,10 +9,39 @@ pub mod python;
 pub mod rust;
 pub mod test_provider;
 
+/// First and last line (1-based, inclusive) of a test in its file
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TestLines {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Lines are written as `<START>:<END>`
+impl fmt::Display for TestLines {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.start, self.end)
+    }
+}
+
+impl FromStr for TestLines {
+    type Err = FztError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FztError::InvalidArgument(format!("Invalid test lines `{}`", s));
+        let (start, end) = s.trim().split_once(':').ok_or_else(invalid)?;
+        Ok(Self {
+            start: start.parse().map_err(|_| invalid())?,
+            end: end.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
 pub trait Test {
     fn runtime_argument(&self) -> String;
     fn name(&self) -> String;
     fn file_path(&self) -> String;
+    // `None` if the test could not be located in its file
+    fn lines(&self) -> Option<TestLines>;
 }
 
 pub trait Tests {
@@ -4,13 +4,22 @@ use std::{
 };
 
 use regex::Regex;
-use rustpython_parser::{Mode, lexer::lex, parse_tokens};
+use rustpython_parser::{
+    Mode,
+    ast::{Mod, ModModule, Ranged, Stmt},
+    lexer::lex,
+    parse_tokens,
+};
 
-use crate::{errors::FztError, runtime::FailedTest, utils::file_walking::collect_tests};
+use crate::{
+    errors::FztError,
+    runtime::FailedTest,
+    tests::TestLines,
+    utils::{file::modified_since, file_walking::collect_tests},
+};
 
-fn collect_tests_from_file(path: &Path) -> Result<HashSet<String>, FztError> {
-    let source_code = std::fs::read_to_string(path)?;
-    let tokens = lex(source_code.as_str(), Mode::Module);
+fn parse_module(source_code: &str, path: &Path) -> Result<ModModule, FztError> {
+    let tokens = lex(source_code, Mode::Module);
     let ast = parse_tokens(tokens, Mode::Module, "<embedded>").map_err(|e| {
         FztError::PythonParser(format!(
             "Failed to parse Python file {}: {}",
@@ -18,46 +27,133 @@ fn collect_tests_from_file(path: &Path) -> Result<HashSet<String>, FztError> {
             e
         ))
     })?;
-    let mut tests = HashSet::new();
     match ast {
-        rustpython_parser::ast::Mod::Module(mod_module) => {
-            for stmt in mod_module.body.iter() {
-                match stmt {
-                    rustpython_parser::ast::Stmt::FunctionDef(stmt_function_def) => {
-                        let test_name = stmt_function_def.name.to_string();
-                        if test_name.starts_with("test") {
-                            tests.insert(stmt_function_def.name.to_string());
-                        }
-                    }
-                    rustpython_parser::ast::Stmt::AsyncFunctionDef(stmt_async_function_def) => {
-                        let test_name = stmt_async_function_def.name.to_string();
-                        if test_name.starts_with("test") {
-                            tests.insert(stmt_async_function_def.name.to_string());
-                        }
-                    }
-                    _ => continue,
+        Mod::Module(mod_module) => Ok(mod_module),
+        Mod::Interactive(_) => Err(FztError::PythonParser(
+            "Mod::Interactive not supported".to_string(),
+        )),
+        Mod::Expression(_) => Err(FztError::PythonParser(
+            "Mod::Expression not supported".to_string(),
+        )),
+        Mod::FunctionType(_) => Err(FztError::PythonParser(
+            "Mod::FunctionType not supported".to_string(),
+        )),
+    }
+}
+
+fn collect_tests_from_file(path: &Path) -> Result<HashSet<String>, FztError> {
+    let source_code = std::fs::read_to_string(path)?;
+    let mod_module = parse_module(source_code.as_str(), path)?;
+    let mut tests = HashSet::new();
+    for stmt in mod_module.body.iter() {
+        match stmt {
+            Stmt::FunctionDef(stmt_function_def) => {
+                let test_name = stmt_function_def.name.to_string();
+                if test_name.starts_with("test") {
+                    tests.insert(stmt_function_def.name.to_string());
                 }
             }
-        }
-        rustpython_parser::ast::Mod::Interactive(_) => {
-            return Err(FztError::PythonParser(
-                "Mod::Interactive not supported".to_string(),
-            ));
-        }
-        rustpython_parser::ast::Mod::Expression(_) => {
-            return Err(FztError::PythonParser(
-                "Mod::Expression not supported".to_string(),
-            ));
-        }
-        rustpython_parser::ast::Mod::FunctionType(_) => {
-            return Err(FztError::PythonParser(
-                "Mod::FunctionType not supported".to_string(),
-            ));
+            Stmt::AsyncFunctionDef(stmt_async_function_def) => {
+                let test_name = stmt_async_function_def.name.to_string();
+                if test_name.starts_with("test") {
+                    tests.insert(stmt_async_function_def.name.to_string());
+                }
+            }
+            _ => continue,
         }
     }
     Ok(tests)
 }
 
+fn line_of(source_code: &str, offset: usize) -> usize {
+    source_code[..offset.min(source_code.len())]
+        .matches('\n')
+        .count()
+        + 1
+}
+
+fn function_lines(source_code: &str, stmt: &Stmt) -> Option<(String, TestLines)> {
+    let (name, decorators) = match stmt {
+        Stmt::FunctionDef(function) => (function.name.to_string(), &function.decorator_list),
+        Stmt::AsyncFunctionDef(function) => (function.name.to_string(), &function.decorator_list),
+        _ => return None,
+    };
+    // Decorators like `@pytest.mark.slow` belong to the test
+    let start = decorators
+        .iter()
+        .map(|decorator| decorator.start().to_usize())
+        .chain([stmt.start().to_usize()])
+        .min()
+        .unwrap_or_default();
+    // The range ends after the last character of the body
+    let lines = TestLines {
+        start: line_of(source_code, start),
+        end: line_of(source_code, stmt.end().to_usize().saturating_sub(1)),
+    };
+    Some((name, lines))
+}
+
+/// Lines of the functions of the module and of the methods of its classes
+fn functions(source_code: &str, path: &Path) -> Result<Vec<(String, TestLines)>, FztError> {
+    let mod_module = parse_module(source_code, path)?;
+    Ok(mod_module
+        .body
+        .iter()
+        .flat_map(|stmt| match stmt {
+            Stmt::ClassDef(class) => class.body.iter().collect(),
+            _ => vec![stmt],
+        })
+        .filter_map(|stmt| function_lines(source_code, stmt))
+        .collect())
+}
+
+/// Locates the tests of files changed since `timestamp` or with tests not located yet.
+/// Lines are stored by the runtime argument of the test, returns if lines changed.
+pub fn update_test_lines(
+    root_folder: &str,
+    timestamp: u128,
+    tests: &HashMap<String, HashSet<String>>,
+    lines: &mut HashMap<String, TestLines>,
+) -> bool {
+    let mut updated = false;
+    let mut updated_lines = HashMap::new();
+    for (path, tests) in tests.iter() {
+        let file = Path::new(root_folder).join(path);
+        let changed = modified_since(&file, timestamp);
+        let located = tests
+            .iter()
+            .all(|test| lines.contains_key(&format!("{}::{}", path, test)));
+        if !changed && located {
+            tests.iter().for_each(|test| {
+                let key = format!("{}::{}", path, test);
+                if let Some(test_lines) = lines.get(&key) {
+                    updated_lines.insert(key, *test_lines);
+                }
+            });
+            continue;
+        }
+        // Files that can not be parsed keep tests without lines
+        let functions: HashMap<String, TestLines> = std::fs::read_to_string(&file)
+            .map_err(FztError::from)
+            .and_then(|source_code| functions(source_code.as_str(), &file))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        tests.iter().for_each(|test| {
+            let key = format!("{}::{}", path, test);
+            let test_lines = functions.get(test).copied();
+            if test_lines != lines.get(&key).copied() {
+                updated = true;
+            }
+            if let Some(test_lines) = test_lines {
+                updated_lines.insert(key, test_lines);
+            }
+        });
+    }
+    *lines = updated_lines;
+    updated
+}
+
 pub fn update_tests(
     root_folder: &str,
     timestamp: &mut u128,
@@ -163,6 +259,44 @@ mod tests {
         assert_eq!(tests, expected_tests);
     }
 
+    #[test]
+    fn locate_tests() {
+        let mut path = std::env::current_dir().unwrap();
+        path.push("src/tests/python/test_data");
+        let (_temp_dir, dir_path) = copy_dict(path.as_path()).unwrap();
+        let test_path = dir_path.as_path().to_str().unwrap();
+        let mut tests = HashMap::new();
+        update_tests(test_path, &mut 0, &mut tests, false).unwrap();
+        let mut lines = HashMap::new();
+        assert!(update_test_lines(test_path, 0, &tests, &mut lines));
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines.get("berlin/hamburg/test_hamburg.py::test_hamburg_harburg"),
+            Some(&TestLines { start: 4, end: 5 })
+        );
+
+        let time_stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        assert!(!update_test_lines(
+            test_path, time_stamp, &tests, &mut lines
+        ));
+
+        // Move test, file timestamps can lag behind the system time
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::fs::write(
+            Path::new(test_path).join("berlin/hamburg/test_hamburg.py"),
+            "def test_hamburg_harburg():\n    print()\n\n\ndef test_hamburg():\n    print()\n",
+        )
+        .unwrap();
+        assert!(update_test_lines(test_path, time_stamp, &tests, &mut lines));
+        assert_eq!(
+            lines.get("berlin/hamburg/test_hamburg.py::test_hamburg"),
+            Some(&TestLines { start: 5, end: 6 })
+        );
+    }
+
     #[test]
     fn collect_failed() {
         let failed_tests: Vec<FailedTest> = vec![
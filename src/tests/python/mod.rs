@@ -1,4 +1,4 @@
-mod helper;
+pub(crate) mod helper;
 pub mod pytest;
 pub mod python_test;
 pub mod rust_python;
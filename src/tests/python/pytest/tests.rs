@@ -8,9 +8,9 @@ use crate::{
     errors::FztError,
     runtime::FailedTest,
     tests::{
-        Test, Tests,
+        Test, TestLines, Tests,
         python::{
-            helper::{parse_failed_tests, update_tests},
+            helper::{parse_failed_tests, update_test_lines, update_tests},
             python_test::PythonTest,
         },
     },
@@ -47,6 +47,9 @@ pub struct PytestTests {
     pub timestamp: u128,
     pub tests: HashMap<String, HashSet<String>>,
     pub failed_tests: HashMap<String, HashSet<String>>,
+    // Lines of the tests by runtime argument
+    #[serde(default)]
+    pub lines: HashMap<String, TestLines>,
 }
 
 impl PytestTests {
@@ -60,6 +63,7 @@ impl PytestTests {
             timestamp,
             tests,
             failed_tests: HashMap::new(),
+            lines: HashMap::new(),
         }
     }
 
@@ -69,6 +73,7 @@ impl PytestTests {
             timestamp: 0,
             tests: HashMap::new(),
             failed_tests: HashMap::new(),
+            lines: HashMap::new(),
         }
     }
 
@@ -116,7 +121,11 @@ impl Tests for PytestTests {
         let mut output = vec![];
         self.tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(PythonTest::new(path.clone(), test.clone()));
+                let key = format!("{}::{}", path, test);
+                output.push(
+                    PythonTest::new(path.clone(), test.clone())
+                        .with_lines(self.lines.get(&key).copied()),
+                );
             });
         });
         output
@@ -125,6 +134,7 @@ impl Tests for PytestTests {
     fn update(&mut self) -> Result<bool, FztError> {
         let files_filtered_out = filter_out_deleted_files(&self.root_folder, &mut self.tests);
         // TODO: also update failed tests, check if entries still exist
+        let timestamp = self.timestamp;
         let updated = update_tests(
             self.root_folder.as_str(),
             &mut self.timestamp,
@@ -134,7 +144,13 @@ impl Tests for PytestTests {
         if updated {
             self.parse_python_tests(get_pytests()?.as_str())?;
         }
-        Ok(updated || files_filtered_out)
+        let updated_lines = update_test_lines(
+            self.root_folder.as_str(),
+            timestamp,
+            &self.tests,
+            &mut self.lines,
+        );
+        Ok(updated || files_filtered_out || updated_lines)
     }
 
     fn update_failed(&mut self, failed_tests_output: &[FailedTest]) -> bool {
@@ -151,7 +167,11 @@ impl Tests for PytestTests {
         let mut output = vec![];
         self.failed_tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(PythonTest::new(path.clone(), test.clone()));
+                let key = format!("{}::{}", path, test);
+                output.push(
+                    PythonTest::new(path.clone(), test.clone())
+                        .with_lines(self.lines.get(&key).copied()),
+                );
             });
         });
         output
@@ -164,9 +184,8 @@ impl Tests for PytestTests {
         todo!()
     }
 
-    #[allow(unreachable_code)]
+    // Coverage is not supported, so no test is covered
     fn get_covered_tests(&self) -> Vec<impl Test> {
-        todo!();
         Vec::<PythonTest>::new()
     }
 }
@@ -1,17 +1,31 @@
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::{FztError, tests::Test};
+use crate::{
+    FztError,
+    tests::{Test, TestLines},
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct PythonTest {
     pub path: String,
     pub test: String,
+    #[serde(skip)]
+    pub lines: Option<TestLines>,
 }
 
 impl PythonTest {
     pub fn new(path: String, test: String) -> Self {
-        Self { path, test }
+        Self {
+            path,
+            test,
+            lines: None,
+        }
+    }
+
+    pub fn with_lines(mut self, lines: Option<TestLines>) -> Self {
+        self.lines = lines;
+        self
     }
 
     pub fn try_from_pytest_test(test: &str) -> Result<Self, FztError> {
@@ -29,10 +43,7 @@ impl PythonTest {
                 "Parsing Pytest failed: {}",
                 test
             )))?;
-        Ok(Self {
-            path,
-            test: test_name,
-        })
+        Ok(Self::new(path, test_name))
     }
 }
 
@@ -48,4 +59,8 @@ impl Test for PythonTest {
     fn file_path(&self) -> String {
         self.path.clone()
     }
+
+    fn lines(&self) -> Option<TestLines> {
+        self.lines
+    }
 }
@@ -10,9 +10,9 @@ use crate::{
     errors::FztError,
     runtime::FailedTest,
     tests::{
-        Test, Tests,
+        Test, TestLines, Tests,
         python::{
-            helper::{parse_failed_tests, update_tests},
+            helper::{parse_failed_tests, update_test_lines, update_tests},
             python_test::PythonTest,
         },
     },
@@ -34,6 +34,9 @@ pub struct RustPythonTests {
     pub failed_tests: HashMap<String, HashSet<String>>,
     pub file_coverage: HashMap<String, CoverageRustPythonTests>,
     pub uncovered_tests: HashSet<PythonTest>,
+    // Lines of the tests by runtime argument
+    #[serde(default)]
+    pub lines: HashMap<String, TestLines>,
 }
 
 impl RustPythonTests {
@@ -51,6 +54,7 @@ impl RustPythonTests {
             failed_tests: HashMap::new(),
             file_coverage: HashMap::new(),
             uncovered_tests: HashSet::new(),
+            lines: HashMap::new(),
         }
     }
 
@@ -63,11 +67,13 @@ impl RustPythonTests {
             failed_tests: HashMap::new(),
             file_coverage: HashMap::new(),
             uncovered_tests: HashSet::new(),
+            lines: HashMap::new(),
         }
     }
 
     fn update_tests(&mut self) -> Result<bool, FztError> {
         let files_filtered_out = filter_out_deleted_files(&self.root_folder, &mut self.tests);
+        let timestamp = self.timestamp;
         let updated_tests = update_tests(
             self.root_folder.as_str(),
             &mut self.timestamp,
@@ -92,7 +98,13 @@ impl RustPythonTests {
                     .map_or(false, |existing_tests| existing_tests.contains(test))
             });
         }
-        Ok(updated_tests || files_filtered_out)
+        let updated_lines = update_test_lines(
+            self.root_folder.as_str(),
+            timestamp,
+            &self.tests,
+            &mut self.lines,
+        );
+        Ok(updated_tests || files_filtered_out || updated_lines)
     }
 
     fn update_uncovered_tests(&mut self) {
@@ -109,10 +121,7 @@ impl RustPythonTests {
             .map(|(path, tests)| {
                 tests
                     .iter()
-                    .map(|test| PythonTest {
-                        path: path.clone(),
-                        test: test.clone(),
-                    })
+                    .map(|test| PythonTest::new(path.clone(), test.clone()))
                     .collect::<Vec<_>>()
             })
             .flatten()
@@ -142,7 +151,11 @@ impl Tests for RustPythonTests {
         let mut output = vec![];
         self.tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(PythonTest::new(path.clone(), test.clone()));
+                let key = format!("{}::{}", path, test);
+                output.push(
+                    PythonTest::new(path.clone(), test.clone())
+                        .with_lines(self.lines.get(&key).copied()),
+                );
             });
         });
         output
@@ -173,7 +186,11 @@ impl Tests for RustPythonTests {
         let mut output = vec![];
         self.failed_tests.iter().for_each(|(path, tests)| {
             tests.iter().for_each(|test| {
-                output.push(PythonTest::new(path.clone(), test.clone()));
+                let key = format!("{}::{}", path, test);
+                output.push(
+                    PythonTest::new(path.clone(), test.clone())
+                        .with_lines(self.lines.get(&key).copied()),
+                );
             });
         });
         output
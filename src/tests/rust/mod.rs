@@ -4,6 +4,7 @@ mod helper;
 pub mod mod_resolver;
 pub mod rust_test;
 pub mod rust_test_parser;
+pub mod test_lines;
 
 pub trait ParseRustTest {
     fn parse_tests(&self) -> Result<Vec<(Vec<String>, String)>, FztError>;
@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    fs,
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -10,10 +11,18 @@ use crate::{
     errors::FztError,
     runtime::FailedTest,
     tests::{
-        Test, Tests,
-        rust::{ParseRustTest, mod_resolver::get_module_paths, rust_test_parser::RustTestParser},
+        Test, TestLines, Tests,
+        rust::{
+            ParseRustTest,
+            mod_resolver::get_module_paths,
+            rust_test_parser::RustTestParser,
+            test_lines::{find_test_lines, functions},
+        },
+    },
+    utils::{
+        file::{get_file_modification_timestamp, modified_since},
+        path_resolver::get_relative_path,
     },
-    utils::{file::get_file_modification_timestamp, path_resolver::get_relative_path},
 };
 
 use super::helper::parse_failed_tests;
@@ -31,11 +40,19 @@ pub struct CoverageRustTests {
     pub tests: HashSet<RustTestItem>,
 }
 
+impl RustTest {
+    pub fn runtime_argument(&self) -> String {
+        format!("{}::{}", self.module_path.join("::"), self.method_name)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct RustTestItem {
     pub path: String,
     pub module_path: String,
     pub test: String,
+    #[serde(skip)]
+    pub lines: Option<TestLines>,
 }
 
 impl RustTestItem {
@@ -44,9 +61,15 @@ impl RustTestItem {
             path,
             module_path,
             test,
+            lines: None,
         }
     }
 
+    pub fn with_lines(mut self, lines: Option<TestLines>) -> Self {
+        self.lines = lines;
+        self
+    }
+
     pub fn try_from_cargo_test(
         test: &str,
         module_paths: &HashMap<Vec<String>, PathBuf>,
@@ -85,6 +108,10 @@ impl Test for RustTestItem {
     fn file_path(&self) -> String {
         self.path.clone()
     }
+
+    fn lines(&self) -> Option<TestLines> {
+        self.lines
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -98,6 +125,9 @@ pub struct RustTests {
     pub module_paths: HashMap<Vec<String>, PathBuf>,
     pub file_coverage: HashMap<String, CoverageRustTests>,
     pub uncovered_tests: HashSet<RustTestItem>,
+    // Lines of the tests by runtime argument
+    #[serde(default)]
+    pub lines: HashMap<String, TestLines>,
 }
 
 impl RustTests {
@@ -111,6 +141,7 @@ impl RustTests {
             file_coverage: HashMap::new(),
             module_paths: HashMap::new(),
             uncovered_tests: HashSet::new(),
+            lines: HashMap::new(),
         }
     }
 
@@ -131,6 +162,7 @@ impl RustTests {
         let updated = if !up_to_date {
             self.resolve_module_paths()?;
             self.refill_tests(cargo_tests)?;
+            self.update_lines(0);
             self.timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
             true
         } else {
@@ -149,7 +181,12 @@ impl RustTests {
                     .map(|rust_test| rust_test.clone())
                     .collect();
             }
-            false
+            // Tests can move without changing the test list
+            let lines_updated = self.update_lines(self.timestamp);
+            if lines_updated {
+                self.timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+            }
+            lines_updated
         };
         self.failed_tests
             .retain(|path, _| self.tests.contains_key(path));
@@ -165,6 +202,46 @@ impl RustTests {
         Ok(updated)
     }
 
+    // Locates the tests of files changed since `timestamp` or with tests not located yet,
+    // returns if the lines of a test changed
+    fn update_lines(&mut self, timestamp: u128) -> bool {
+        let mut updated = false;
+        let mut lines = HashMap::new();
+        for (path, tests) in self.tests.iter() {
+            let file = Path::new(&self.root_folder).join(path);
+            let changed = modified_since(&file, timestamp);
+            let located = tests
+                .iter()
+                .all(|test| self.lines.contains_key(&test.runtime_argument()));
+            if !changed && located {
+                tests.iter().for_each(|test| {
+                    let key = test.runtime_argument();
+                    if let Some(test_lines) = self.lines.get(&key) {
+                        lines.insert(key, *test_lines);
+                    }
+                });
+                continue;
+            }
+            // Files that can not be parsed keep tests without lines
+            let functions = fs::read_to_string(&file)
+                .map_err(FztError::from)
+                .and_then(|source| functions(source.as_str()))
+                .unwrap_or_default();
+            tests.iter().for_each(|test| {
+                let key = test.runtime_argument();
+                let test_lines = find_test_lines(functions.as_slice(), test);
+                if test_lines != self.lines.get(&key).copied() {
+                    updated = true;
+                }
+                if let Some(test_lines) = test_lines {
+                    lines.insert(key, test_lines);
+                }
+            });
+        }
+        self.lines = lines;
+        updated
+    }
+
     fn update_uncovered_tests(&mut self) {
         // Remove test related to deleted files
         self.file_coverage
@@ -277,6 +354,7 @@ impl Tests for RustTests {
                             test.module_path.join("::"),
                             test.method_name.clone(),
                         )
+                        .with_lines(self.lines.get(&test.runtime_argument()).copied())
                     })
                     .collect::<Vec<_>>()
             })
@@ -312,6 +390,7 @@ impl Tests for RustTests {
                             test.module_path.join("::"),
                             test.method_name.clone(),
                         )
+                        .with_lines(self.lines.get(&test.runtime_argument()).copied())
                     })
                     .collect::<Vec<_>>()
             })
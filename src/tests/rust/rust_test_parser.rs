@@ -41,7 +41,7 @@ impl ParseRustTest for RustTestParser {
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 let line = line?;
-                println!("{}", line);
+                eprintln!("{}", line);
             }
         }
 
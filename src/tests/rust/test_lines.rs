@@ -0,0 +1,100 @@
+use syn::{ItemFn, ItemMod, spanned::Spanned, visit::Visit};
+
+use crate::{errors::FztError, tests::TestLines};
+
+use super::rust_test::RustTest;
+
+/// Function of a file with the inline modules it is defined in
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustFunction {
+    pub module_path: Vec<String>,
+    pub name: String,
+    pub lines: TestLines,
+}
+
+#[derive(Default)]
+struct FunctionVisitor {
+    module_path: Vec<String>,
+    functions: Vec<RustFunction>,
+}
+
+impl<'ast> Visit<'ast> for FunctionVisitor {
+    fn visit_item_mod(&mut self, item_mod: &'ast ItemMod) {
+        // Modules without content live in their own file
+        if item_mod.content.is_some() {
+            self.module_path.push(item_mod.ident.to_string());
+            syn::visit::visit_item_mod(self, item_mod);
+            self.module_path.pop();
+        }
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast ItemFn) {
+        // The span includes the attributes, e.g. `#[test]`
+        let span = item_fn.span();
+        self.functions.push(RustFunction {
+            module_path: self.module_path.clone(),
+            name: item_fn.sig.ident.to_string(),
+            lines: TestLines {
+                start: span.start().line,
+                end: span.end().line,
+            },
+        });
+        syn::visit::visit_item_fn(self, item_fn);
+    }
+}
+
+pub fn functions(source: &str) -> Result<Vec<RustFunction>, FztError> {
+    let file = syn::parse_file(source)?;
+    let mut visitor = FunctionVisitor::default();
+    visitor.visit_file(&file);
+    Ok(visitor.functions)
+}
+
+/// Lines of the test, the module path of the test ends with the inline modules
+/// of the function. The function in the most nested matching module is used.
+pub fn find_test_lines(functions: &[RustFunction], test: &RustTest) -> Option<TestLines> {
+    functions
+        .iter()
+        .filter(|function| {
+            function.name == test.method_name && test.module_path.ends_with(&function.module_path)
+        })
+        .max_by_key(|function| function.module_path.len())
+        .map(|function| function.lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn locate_nested_tests() {
+        let source = "fn one() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn one() {\n        assert!(true);\n    }\n\n    mod nested {\n        #[test]\n        fn one() {}\n    }\n}\n";
+        let functions = functions(source).unwrap();
+        let test = |module_path: &[&str]| RustTest {
+            module_path: module_path
+                .iter()
+                .map(|module| module.to_string())
+                .collect(),
+            method_name: "one".to_string(),
+        };
+        assert_eq!(
+            find_test_lines(&functions, &test(&["a", "tests"])),
+            Some(TestLines { start: 5, end: 8 })
+        );
+        assert_eq!(
+            find_test_lines(&functions, &test(&["a", "tests", "nested"])),
+            Some(TestLines { start: 11, end: 12 })
+        );
+        assert_eq!(
+            find_test_lines(
+                &functions,
+                &RustTest {
+                    module_path: vec!["a".to_string(), "tests".to_string()],
+                    method_name: "two".to_string(),
+                }
+            ),
+            None
+        );
+    }
+}
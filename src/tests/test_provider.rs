@@ -5,11 +5,13 @@ use std::{
     str::FromStr,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::search_engine::Append;
 
-use super::{Test, Tests};
+use super::{Test, TestLines, Tests};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SelectGranularity {
     Test,
     File,
@@ -54,6 +56,20 @@ impl From<Append> for SelectGranularity {
     }
 }
 
+fn is_ignored(file_path: &str, ignored_paths: &[String]) -> bool {
+    ignored_paths.iter().any(|ignored| {
+        let ignored = ignored.trim_end_matches('/');
+        file_path == ignored || file_path.starts_with(format!("{ignored}/").as_str())
+    })
+}
+
+fn filter_ignored<T: Test>(tests: Vec<T>, ignored_paths: &[String]) -> Vec<T> {
+    tests
+        .into_iter()
+        .filter(|test| !is_ignored(test.file_path().as_str(), ignored_paths))
+        .collect()
+}
+
 fn extract_test_selection<T: Test>(tests: &[T]) -> HashMap<String, String> {
     HashMap::from_iter(
         tests
@@ -62,6 +78,13 @@ fn extract_test_selection<T: Test>(tests: &[T]) -> HashMap<String, String> {
     )
 }
 
+fn extract_test_lines<T: Test>(tests: &[T]) -> HashMap<String, TestLines> {
+    tests
+        .iter()
+        .filter_map(|test| test.lines().map(|lines| (test.name(), lines)))
+        .collect()
+}
+
 fn extract_file_section<T: Test>(tests: &[T]) -> HashMap<String, Vec<String>> {
     let mut file_section: HashMap<String, Vec<String>> = HashMap::new();
     tests.iter().for_each(|test| {
@@ -102,6 +125,8 @@ pub struct TestProvider {
     file_selection: HashMap<String, Vec<String>>,
     dictionary_selection: HashMap<String, Vec<String>>,
     runtime_selection: HashSet<String>,
+    // Lines of the tests in their file by test name, used by the test preview
+    test_lines: HashMap<String, TestLines>,
     // If set, runtime arguments are always returned by
     // this test provider. This allows a level of
     // abstraction of selection items and the corresponding
@@ -110,39 +135,52 @@ pub struct TestProvider {
 }
 
 impl TestProvider {
-    pub fn new<T: Tests>(tests: &T) -> Self {
-        let available_tests = tests.tests();
+    pub fn new<T: Tests>(tests: &T, ignored_paths: &[String]) -> Self {
+        let available_tests = filter_ignored(tests.tests(), ignored_paths);
         Self {
             test_selection: extract_test_selection(available_tests.as_slice()),
             file_selection: extract_file_section(available_tests.as_slice()),
             dictionary_selection: extract_dictionary_selection(available_tests.as_slice()),
             runtime_selection: extract_runtime_selection(available_tests.as_slice()),
+            test_lines: extract_test_lines(available_tests.as_slice()),
             default_test_provider: None,
         }
     }
 
-    pub fn new_failed<T: Tests>(tests: &T) -> Self {
-        let available_tests = tests.tests_failed();
+    /// Failed tests, except the `excluded_tests` given by name
+    pub fn new_failed<T: Tests>(
+        tests: &T,
+        ignored_paths: &[String],
+        excluded_tests: &HashSet<String>,
+    ) -> Self {
+        let mut available_tests = filter_ignored(tests.tests_failed(), ignored_paths);
+        available_tests.retain(|test| !excluded_tests.contains(&test.name()));
         Self {
             test_selection: extract_test_selection(available_tests.as_slice()),
             file_selection: extract_file_section(available_tests.as_slice()),
             dictionary_selection: extract_dictionary_selection(available_tests.as_slice()),
             runtime_selection: extract_runtime_selection(available_tests.as_slice()),
-            default_test_provider: Some(Box::new(TestProvider::new(tests))),
+            test_lines: extract_test_lines(available_tests.as_slice()),
+            default_test_provider: Some(Box::new(TestProvider::new(tests, ignored_paths))),
         }
     }
 
-    pub fn new_covered_tests<T: Tests>(tests: &T) -> Self {
-        let available_tests = tests.get_covered_tests();
+    pub fn new_covered_tests<T: Tests>(tests: &T, ignored_paths: &[String]) -> Self {
+        let available_tests = filter_ignored(tests.get_covered_tests(), ignored_paths);
         Self {
             test_selection: extract_test_selection(available_tests.as_slice()),
             file_selection: extract_file_section(available_tests.as_slice()),
             dictionary_selection: extract_dictionary_selection(available_tests.as_slice()),
             runtime_selection: extract_runtime_selection(available_tests.as_slice()),
-            default_test_provider: Some(Box::new(TestProvider::new(tests))),
+            test_lines: extract_test_lines(available_tests.as_slice()),
+            default_test_provider: Some(Box::new(TestProvider::new(tests, ignored_paths))),
         }
     }
 
+    pub fn test_lines(&self) -> &HashMap<String, TestLines> {
+        &self.test_lines
+    }
+
     pub fn select_option(&self, select_granularity: &SelectGranularity) -> Vec<&str> {
         match select_granularity {
             SelectGranularity::Test => self
@@ -243,3 +281,46 @@ impl TestProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::rust::rust_test::{RustTest, RustTests};
+
+    #[test]
+    fn ignore_paths() {
+        let ignored_paths = vec!["tests/slow/".to_string(), "vendor".to_string()];
+        assert!(is_ignored("tests/slow/test_big.py", &ignored_paths));
+        assert!(is_ignored("vendor", &ignored_paths));
+        assert!(is_ignored("vendor/lib/test_lib.py", &ignored_paths));
+        assert!(!is_ignored("tests/slower/test_big.py", &ignored_paths));
+        assert!(!is_ignored("src/vendor/test_lib.py", &ignored_paths));
+    }
+
+    #[test]
+    fn all_failed_tests() {
+        let test = |name: &str| RustTest {
+            module_path: vec!["tests".to_string()],
+            method_name: name.to_string(),
+        };
+        let mut tests = RustTests::new_empty("/project".to_string());
+        tests.tests = HashMap::from([
+            ("src/a.rs".to_string(), vec![test("one"), test("two")]),
+            ("src/b/c.rs".to_string(), vec![test("three")]),
+        ]);
+        tests.failed_tests = HashMap::from([("src/a.rs".to_string(), vec![test("two")])]);
+        let provider = TestProvider::new_failed(&tests, &[], &HashSet::new());
+        for granularity in [
+            SelectGranularity::Test,
+            SelectGranularity::File,
+            SelectGranularity::Directory,
+            SelectGranularity::RunTime,
+        ] {
+            let all = provider.all(&granularity);
+            assert_eq!(
+                provider.runtime_arguments(&SelectGranularity::RunTime, all.as_slice()),
+                vec!["tests::two".to_string()]
+            );
+        }
+    }
+}
@@ -1,4 +1,16 @@
-use std::{fs::metadata, time::UNIX_EPOCH};
+use std::{
+    env,
+    fs::{File, OpenOptions, metadata, remove_file, rename},
+    io::Write,
+    path::Path,
+    process::Command,
+    time::UNIX_EPOCH,
+};
+
+use colored::Colorize;
+use tempfile::NamedTempFile;
+
+use crate::errors::FztError;
 
 pub fn get_file_modification_timestamp(path: &str) -> u128 {
     metadata(path)
@@ -16,3 +28,112 @@ pub fn get_file_modification_timestamp(path: &str) -> u128 {
         .expect("System clock may have gone backwards")
         .as_millis()
 }
+
+/// Whether the file was modified after `timestamp`, files without a timestamp count as modified
+pub fn modified_since(path: &Path, timestamp: u128) -> bool {
+    metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .is_none_or(|modified| modified.as_millis() > timestamp)
+}
+
+/// Write to a temporary file next to `path` and rename it, so readers never see a partial file
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), FztError> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut file = NamedTempFile::new_in(parent)?;
+    file.write_all(content.as_bytes())?;
+    file.flush()?;
+    file.persist(path).map_err(|error| error.error)?;
+    Ok(())
+}
+
+/// Blocks until the advisory lock beside `path` is acquired, it is released once the file is dropped
+pub fn lock_exclusive(path: &Path) -> Result<File, FztError> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)?;
+    lock.lock()?;
+    Ok(lock)
+}
+
+/// Remove the file while holding its lock, a missing file is skipped.
+/// The lock file stays, other processes may still hold or wait for it.
+pub fn remove_locked(path: &Path) -> Result<(), FztError> {
+    let _lock = lock_exclusive(path)?;
+    if path.exists() {
+        remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Move a file that can not be parsed aside, so it can be inspected while fzt starts fresh
+pub fn backup_corrupted(path: &Path) -> Result<(), FztError> {
+    let mut backup_path = path.as_os_str().to_owned();
+    backup_path.push(".corrupt");
+    rename(path, &backup_path)?;
+    eprintln!(
+        "{}",
+        format!(
+            "{} is corrupted, moved it to {}",
+            path.display(),
+            Path::new(&backup_path).display()
+        )
+        .yellow()
+    );
+    Ok(())
+}
+
+/// Open a file in the user's preferred editor
+pub fn open_in_editor(path: &Path) -> Result<(), FztError> {
+    // Try to get the editor from environment variables
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            // Fallback editors based on platform
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else if cfg!(target_os = "macos") {
+                "open".to_string()
+            } else {
+                // Unix/Linux - try common editors
+                "vim".to_string()
+            }
+        });
+
+    let status = Command::new(&editor).arg(path).status().map_err(|e| {
+        FztError::RuntimeError(format!("Failed to open editor '{}': {}", editor, e))
+    })?;
+
+    if !status.success() {
+        return Err(FztError::RuntimeError(format!(
+            "Editor '{}' exited with non-zero status",
+            editor
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_file_atomic() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        write_atomic(path.as_path(), "old").unwrap();
+        write_atomic(path.as_path(), "new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
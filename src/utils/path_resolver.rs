@@ -20,3 +20,50 @@ pub fn get_relative_path(parent: &str, full_path: &str) -> Result<String, FztErr
 
     Ok(relative_path.to_string())
 }
+
+/// Map a selection item like `<PATH>` or `<PATH>::<TEST>` given relative to `working_dir`
+/// to be relative to `root`. Items whose path does not exist below `working_dir` are unchanged.
+pub fn relative_to_root(item: &str, working_dir: &Path, root: &Path) -> String {
+    let Ok(prefix) = working_dir.strip_prefix(root) else {
+        return item.to_string();
+    };
+    if prefix.as_os_str().is_empty() {
+        return item.to_string();
+    }
+    let (path, rest) = match item.split_once("::") {
+        Some((path, rest)) => (path, Some(rest)),
+        None => (item, None),
+    };
+    if path.is_empty() || !working_dir.join(path).exists() {
+        return item.to_string();
+    }
+    let path = prefix.join(path).to_string_lossy().to_string();
+    match rest {
+        Some(rest) => format!("{path}::{rest}"),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn map_item_to_root() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let working_dir = root.join("tests");
+        fs::create_dir_all(working_dir.join("api")).unwrap();
+        fs::write(working_dir.join("api/test_login.py"), "").unwrap();
+        assert_eq!(
+            relative_to_root("api/test_login.py::test_ok", &working_dir, root),
+            "tests/api/test_login.py::test_ok"
+        );
+        assert_eq!(relative_to_root("api", &working_dir, root), "tests/api");
+        assert_eq!(relative_to_root("test_ok", &working_dir, root), "test_ok");
+        assert_eq!(relative_to_root("api", root, root), "api");
+    }
+}
@@ -28,12 +28,15 @@ pub fn watch<SE: SearchEngine + Clone + Send>(config: RunnerConfig<SE>) -> Resul
             local_config.mode = crate::RunnerMode::Last;
             local_config.update_history = false;
         } else {
-            if local_config.mode != crate::RunnerMode::All {
+            if !matches!(
+                local_config.mode,
+                crate::RunnerMode::All | crate::RunnerMode::Filter(_) | crate::RunnerMode::Suite(_)
+            ) {
                 init_run = false;
             }
         }
         let handle = std::thread::spawn(move || -> Result<(), FztError> {
-            local_config.into_runner()?.run(Some(rx))
+            local_config.into_runner()?.run(Some(rx)).map(|_| ())
         });
         let event = loop {
             let event = notify_rx.recv()??;